@@ -0,0 +1,95 @@
+// A small fork-join pool on top of `Worker`/`Stealer`: summing a range by
+// splitting it in half until the pieces are small. Only the first thread gets
+// the initial task, everyone else starts out stealing.
+//
+//     cargo run --example work_stealing
+
+use std::hint::black_box;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use unsafe_rust_deque::work_stealing::{Stealer, Worker};
+
+const THREADS: usize = 4;
+const LEAF: u64 = 10_000;
+
+struct Pool {
+    stealers: Vec<Stealer<Range<u64>>>,
+    // Tasks pushed but not finished. Zero means there's nothing left anywhere,
+    // including inside tasks that are still running and may split.
+    pending: AtomicUsize,
+    total: AtomicU64,
+}
+
+impl Pool {
+    fn run(&self, id: usize, local: &Worker<Range<u64>>) -> (usize, usize) {
+        let mut ran = 0;
+        let mut stole = 0;
+        while self.pending.load(Ordering::Acquire) > 0 {
+            let task = local.pop().or_else(|| {
+                let task = (1..THREADS)
+                    .map(|offset| &self.stealers[(id + offset) % THREADS])
+                    .find_map(|victim| victim.steal_half_into(local));
+                stole += task.is_some() as usize;
+                task
+            });
+
+            match task {
+                Some(range) => {
+                    self.execute(range, local);
+                    ran += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        (ran, stole)
+    }
+
+    fn execute(&self, range: Range<u64>, local: &Worker<Range<u64>>) {
+        if range.end - range.start > LEAF {
+            let mid = range.start + (range.end - range.start) / 2;
+            // Count the children before retiring the parent, so `pending` never
+            // hits zero while there's still work.
+            self.pending.fetch_add(2, Ordering::AcqRel);
+            local.push(range.start..mid);
+            local.push(mid..range.end);
+        } else {
+            // `black_box` keeps the compiler from turning this into a formula, so
+            // the leaves cost enough for stealing to pay off.
+            let sum = range.map(black_box).sum::<u64>();
+            self.total.fetch_add(sum, Ordering::Relaxed);
+        }
+        self.pending.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+fn main() {
+    let n = 100_000_000;
+    let workers: Vec<Worker<Range<u64>>> = (0..THREADS).map(|_| Worker::new()).collect();
+    let pool = Arc::new(Pool {
+        stealers: workers.iter().map(Worker::stealer).collect(),
+        pending: AtomicUsize::new(1),
+        total: AtomicU64::new(0),
+    });
+    workers[0].push(0..n);
+
+    let handles: Vec<_> = workers
+        .into_iter()
+        .enumerate()
+        .map(|(id, local)| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || pool.run(id, &local))
+        })
+        .collect();
+
+    for (id, handle) in handles.into_iter().enumerate() {
+        let (ran, stole) = handle.join().unwrap();
+        println!("worker {}: ran {} tasks, stole {} times", id, ran, stole);
+    }
+
+    let total = pool.total.load(Ordering::Relaxed);
+    println!("sum of 0..{} = {}", n, total);
+    assert_eq!(total, n * (n - 1) / 2);
+}
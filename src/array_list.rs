@@ -0,0 +1,584 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+
+// Stands in for a null link.
+const NIL: usize = usize::MAX;
+
+// A doubly-linked list living entirely inside an array of `N` slots, linked
+// by index, so it never allocates and can be moved freely. Unused slots are
+// chained through `next` into a free list. Anything that would need another
+// slot when they're all taken hands the element back instead.
+pub struct ArrayLinkedList<T, const N: usize> {
+    slots: [Slot<T>; N],
+    front: usize,
+    back: usize,
+    free: usize,
+    len: usize,
+}
+
+struct Slot<T> {
+    prev: usize,
+    next: usize,
+    elem: MaybeUninit<T>,
+}
+
+pub struct Iter<'a, T, const N: usize> {
+    list: &'a ArrayLinkedList<T, N>,
+    front: usize,
+    back: usize,
+    len: usize,
+}
+
+pub struct IterMut<'a, T, const N: usize> {
+    slots: *mut Slot<T>,
+    front: usize,
+    back: usize,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T, const N: usize> {
+    list: ArrayLinkedList<T, N>,
+}
+
+pub struct CursorMut<'a, T, const N: usize> {
+    list: &'a mut ArrayLinkedList<T, N>,
+    current: usize,
+    index: Option<usize>,
+}
+
+impl<T, const N: usize> ArrayLinkedList<T, N> {
+    pub fn new() -> Self {
+        Self {
+            slots: core::array::from_fn(|i| Slot {
+                prev: NIL,
+                next: if i + 1 < N { i + 1 } else { NIL },
+                elem: MaybeUninit::uninit(),
+            }),
+            front: NIL,
+            back: NIL,
+            free: if N > 0 { 0 } else { NIL },
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn push_front(&mut self, elem: T) -> Result<(), T> {
+        self.link(elem, NIL, self.front)
+    }
+
+    pub fn push_back(&mut self, elem: T) -> Result<(), T> {
+        self.link(elem, self.back, NIL)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        (self.front != NIL).then(|| self.unlink(self.front))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        (self.back != NIL).then(|| self.unlink(self.back))
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(self.front)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.front)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.back)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.back)
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    // Like the cursor's splices: if `other` doesn't fit it comes back whole.
+    pub fn append(&mut self, other: Self) -> Result<(), Self> {
+        self.cursor_mut().splice_before(other)
+    }
+
+    // Can't run out of room, the tail has at most as many elements as `self`
+    // had. Moves them one at a time, so it's O(len - at).
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len;
+        assert!(
+            at <= len,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+
+        let mut back = Self::new();
+        for _ in at..len {
+            let elem = self.pop_back().unwrap();
+            let _ = back.push_front(elem);
+        }
+        back
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            list: self,
+            front: self.front,
+            back: self.back,
+            len: self.len,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N> {
+        IterMut {
+            slots: self.slots.as_mut_ptr(),
+            front: self.front,
+            back: self.back,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, N> {
+        CursorMut {
+            list: self,
+            current: NIL,
+            index: None,
+        }
+    }
+
+    fn get(&self, slot: usize) -> Option<&T> {
+        // Only linked slots are ever reached through `front`, `back` and the
+        // links, and those are always initialized.
+        (slot != NIL).then(|| unsafe { self.slots[slot].elem.assume_init_ref() })
+    }
+
+    fn get_mut(&mut self, slot: usize) -> Option<&mut T> {
+        (slot != NIL).then(|| unsafe { self.slots[slot].elem.assume_init_mut() })
+    }
+
+    // Takes a free slot for `elem` and links it in between `prev` and `next`,
+    // which must be neighbours (or NIL at the ends).
+    fn link(&mut self, elem: T, prev: usize, next: usize) -> Result<(), T> {
+        if self.free == NIL {
+            return Err(elem);
+        }
+
+        let slot = self.free;
+        self.free = self.slots[slot].next;
+        self.slots[slot] = Slot {
+            prev,
+            next,
+            elem: MaybeUninit::new(elem),
+        };
+
+        match prev {
+            NIL => self.front = slot,
+            prev => self.slots[prev].next = slot,
+        }
+        match next {
+            NIL => self.back = slot,
+            next => self.slots[next].prev = slot,
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    // `slot` must be linked. Its element moves out and it joins the free list.
+    fn unlink(&mut self, slot: usize) -> T {
+        let Slot { prev, next, .. } = self.slots[slot];
+        match prev {
+            NIL => self.front = next,
+            prev => self.slots[prev].next = next,
+        }
+        match next {
+            NIL => self.back = prev,
+            next => self.slots[next].prev = prev,
+        }
+
+        self.slots[slot].prev = NIL;
+        self.slots[slot].next = self.free;
+        self.free = slot;
+        self.len -= 1;
+        unsafe { self.slots[slot].elem.assume_init_read() }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayLinkedList<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for ArrayLinkedList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayLinkedList<T, N> {
+    fn clone(&self) -> Self {
+        let mut list = Self::new();
+        for elem in self {
+            // Same capacity, so this can't overflow.
+            let _ = list.push_back(elem.clone());
+        }
+        list
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for ArrayLinkedList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for ArrayLinkedList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArrayLinkedList<T, N> {}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArrayLinkedList<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArrayLinkedList<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+// There's no way to hand elements back from here, so running out of room
+// panics. Use `push_back` to keep them.
+impl<T, const N: usize> Extend<T> for ArrayLinkedList<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            if self.push_back(elem).is_err() {
+                panic!("extending past the capacity of {}", N);
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayLinkedList<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for ArrayLinkedList<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state)
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayLinkedList<T, N> {
+    type IntoIter = Iter<'a, T, N>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayLinkedList<T, N> {
+    type IntoIter = IterMut<'a, T, N>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayLinkedList<T, N> {
+    type IntoIter = IntoIter<T, N>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for Iter<'a, T, N> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.front;
+        self.front = self.list.slots[slot].next;
+        self.len -= 1;
+        self.list.get(slot)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for Iter<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let slot = self.back;
+        self.back = self.list.slots[slot].prev;
+        self.len -= 1;
+        self.list.get(slot)
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for Iter<'a, T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+// Goes through a raw pointer so the `&mut` handed out for one slot doesn't
+// overlap a borrow of the whole array.
+impl<'a, T, const N: usize> Iterator for IterMut<'a, T, N> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let slot = self.slots.add(self.front);
+            self.front = (*slot).next;
+            self.len -= 1;
+            Some((*slot).elem.assume_init_mut())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for IterMut<'a, T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        unsafe {
+            let slot = self.slots.add(self.back);
+            self.back = (*slot).prev;
+            self.len -= 1;
+            Some((*slot).elem.assume_init_mut())
+        }
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for IterMut<'a, T, N> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+// Same ghost rules as `LinkedList`'s cursor. Splits and splices move elements
+// between arrays one at a time, so they're O(n) in what moves, and a splice
+// that doesn't fit hands the whole input back untouched.
+impl<'a, T, const N: usize> CursorMut<'a, T, N> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn as_list(&self) -> &ArrayLinkedList<T, N> {
+        self.list
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            NIL => {
+                self.current = self.list.front;
+                self.index = (self.current != NIL).then_some(0);
+            }
+            current => {
+                self.current = self.list.slots[current].next;
+                self.index = self.index.filter(|_| self.current != NIL).map(|i| i + 1);
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            NIL => {
+                self.current = self.list.back;
+                self.index = self.list.len.checked_sub(1);
+            }
+            current => {
+                self.current = self.list.slots[current].prev;
+                self.index = self.index.and_then(|i| i.checked_sub(1));
+            }
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.list.get_mut(self.current)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            NIL => self.list.front,
+            current => self.list.slots[current].next,
+        };
+        self.list.get_mut(next)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            NIL => self.list.back,
+            current => self.list.slots[current].prev,
+        };
+        self.list.get_mut(prev)
+    }
+
+    pub fn insert_before(&mut self, elem: T) -> Result<(), T> {
+        match self.current {
+            NIL => self.list.push_back(elem),
+            current => {
+                let prev = self.list.slots[current].prev;
+                self.list.link(elem, prev, current)?;
+                self.index = self.index.map(|i| i + 1);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn insert_after(&mut self, elem: T) -> Result<(), T> {
+        match self.current {
+            NIL => self.list.push_front(elem),
+            current => {
+                let next = self.list.slots[current].next;
+                self.list.link(elem, current, next)
+            }
+        }
+    }
+
+    // Moves on to the next element, or the ghost if there isn't one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.current == NIL {
+            return None;
+        }
+        let next = self.list.slots[self.current].next;
+        let elem = self.list.unlink(self.current);
+        self.current = next;
+        if next == NIL {
+            self.index = None;
+        }
+        Some(elem)
+    }
+
+    pub fn split_before(&mut self) -> ArrayLinkedList<T, N> {
+        let Some(index) = self.index else {
+            return mem::take(self.list);
+        };
+
+        let mut front = ArrayLinkedList::new();
+        for _ in 0..index {
+            let elem = self.list.pop_front().unwrap();
+            let _ = front.push_back(elem);
+        }
+        self.index = Some(0);
+        front
+    }
+
+    pub fn split_after(&mut self) -> ArrayLinkedList<T, N> {
+        let Some(index) = self.index else {
+            return mem::take(self.list);
+        };
+
+        let mut back = ArrayLinkedList::new();
+        for _ in index + 1..self.list.len {
+            let elem = self.list.pop_back().unwrap();
+            let _ = back.push_front(elem);
+        }
+        back
+    }
+
+    pub fn splice_before(
+        &mut self,
+        mut input: ArrayLinkedList<T, N>,
+    ) -> Result<(), ArrayLinkedList<T, N>> {
+        if input.len > N - self.list.len {
+            return Err(input);
+        }
+        while let Some(elem) = input.pop_front() {
+            let _ = self.insert_before(elem);
+        }
+        Ok(())
+    }
+
+    pub fn splice_after(
+        &mut self,
+        mut input: ArrayLinkedList<T, N>,
+    ) -> Result<(), ArrayLinkedList<T, N>> {
+        if input.len > N - self.list.len {
+            return Err(input);
+        }
+        while let Some(elem) = input.pop_back() {
+            let _ = self.insert_after(elem);
+        }
+        Ok(())
+    }
+}
+
+unsafe impl<'a, T: Send, const N: usize> Send for IterMut<'a, T, N> {}
+unsafe impl<'a, T: Sync, const N: usize> Sync for IterMut<'a, T, N> {}
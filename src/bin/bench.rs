@@ -0,0 +1,388 @@
+use std::collections::{self, VecDeque};
+use std::env;
+use std::hint::black_box;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use unsafe_rust_deque::deque::{algorithms, CursorDeque, DequeCursor, SplittableDeque};
+use unsafe_rust_deque::LinkedList;
+
+const USAGE: &str = "\
+usage: bench [--sizes N,N,...] [--reps N]
+
+Runs every workload on every structure at every size and prints CSV to stdout
+and a summary table to stderr. Build with --release, or the numbers mean little.";
+
+// Everything but the name falls back to the deque traits. Structures with a
+// faster native way of doing something override it, since that's what a user
+// would reach for.
+trait Subject: CursorDeque<u64> + SplittableDeque<u64> {
+    const NAME: &'static str;
+
+    fn sort(&mut self) {
+        algorithms::sort(self);
+    }
+
+    fn get(&self, index: usize) -> Option<&u64> {
+        self.iter().nth(index)
+    }
+
+    // Puts a run of `RUN` elements on each side of the current one, leaving
+    // the cursor where it was. Without a native splice that's one insert per
+    // element.
+    fn splice_around(cursor: &mut Self::CursorMut<'_>) {
+        for elem in 0..RUN {
+            cursor.insert_before(elem);
+        }
+        for elem in (0..RUN).rev() {
+            cursor.insert_after(elem);
+        }
+    }
+}
+
+impl Subject for LinkedList<u64> {
+    const NAME: &'static str = "LinkedList";
+
+    fn sort(&mut self) {
+        LinkedList::sort(self);
+    }
+
+    fn splice_around(cursor: &mut Self::CursorMut<'_>) {
+        cursor.splice_before((0..RUN).collect());
+        cursor.splice_after((0..RUN).collect());
+    }
+}
+
+impl Subject for collections::LinkedList<u64> {
+    const NAME: &'static str = "std LinkedList";
+}
+
+impl Subject for VecDeque<u64> {
+    const NAME: &'static str = "VecDeque";
+
+    fn sort(&mut self) {
+        self.make_contiguous().sort();
+    }
+
+    fn get(&self, index: usize) -> Option<&u64> {
+        VecDeque::get(self, index)
+    }
+}
+
+// How many elements the splicing workloads put in at a time.
+const RUN: u64 = 16;
+
+// Each one builds its input untimed, then returns how long the measured part
+// took and how many operations it did.
+type Workload = fn(usize, &mut Rng) -> (Duration, usize);
+
+fn workloads<D: Subject>() -> [(&'static str, Workload); 7] {
+    [
+        ("push_pop", push_pop::<D>),
+        ("iterate", iterate::<D>),
+        ("cursor_insert", cursor_insert::<D>),
+        ("splice", splice::<D>),
+        ("cursor_splice", cursor_splice::<D>),
+        ("sort", sort::<D>),
+        ("random_access", random_access::<D>),
+    ]
+}
+
+// Grows to `size` from both ends, churns with a random mix of all four ends,
+// then drains.
+fn push_pop<D: Subject>(size: usize, rng: &mut Rng) -> (Duration, usize) {
+    let choices: Vec<u64> = (0..size * 3).map(|_| rng.below(4)).collect();
+    let mut deque = D::default();
+    let mut drained = 0;
+    let elapsed = time(|| {
+        for (i, &choice) in choices[..size].iter().enumerate() {
+            if choice < 2 {
+                deque.push_front(i as u64);
+            } else {
+                deque.push_back(i as u64);
+            }
+        }
+        for (i, &choice) in choices[size..].iter().enumerate() {
+            match (choice, i % 2) {
+                (0, 0) => deque.push_front(i as u64),
+                (_, 0) => deque.push_back(i as u64),
+                (0 | 1, _) => {
+                    black_box(deque.pop_front());
+                }
+                _ => {
+                    black_box(deque.pop_back());
+                }
+            }
+        }
+        while let Some(elem) = deque.pop_back() {
+            black_box(elem);
+            drained += 1;
+        }
+    });
+    (elapsed, size * 3 + drained)
+}
+
+// One pass each way.
+fn iterate<D: Subject>(size: usize, _: &mut Rng) -> (Duration, usize) {
+    let deque = filled::<D>(size);
+    let elapsed = time(|| {
+        let forward: u64 = deque.iter().sum();
+        let backward: u64 = deque.iter().rev().sum();
+        (forward, backward)
+    });
+    (elapsed, size * 2)
+}
+
+// Walks a cursor over the whole thing, inserting after every element.
+fn cursor_insert<D: Subject>(size: usize, _: &mut Rng) -> (Duration, usize) {
+    let mut deque = filled::<D>(size);
+    let elapsed = time(|| {
+        let mut cursor = deque.cursor_mut();
+        cursor.move_next();
+        while let Some(&mut elem) = cursor.current() {
+            cursor.insert_after(elem);
+            cursor.move_next();
+            cursor.move_next();
+        }
+    });
+    (elapsed, size)
+}
+
+// Cuts at a random point, splices a short run in and glues the rest back on.
+fn splice<D: Subject>(size: usize, rng: &mut Rng) -> (Duration, usize) {
+    const ROUNDS: usize = 100;
+    let mut deque = filled::<D>(size);
+    let cuts: Vec<usize> = (0..ROUNDS)
+        .map(|round| rng.below((size + round * RUN as usize + 1) as u64) as usize)
+        .collect();
+    let elapsed = time(|| {
+        for &at in &cuts {
+            let mut back = deque.split_off(at);
+            let mut run = D::default();
+            run.extend(0..RUN);
+            deque.append(&mut run);
+            deque.append(&mut back);
+        }
+    });
+    (elapsed, ROUNDS)
+}
+
+// Walks a cursor over the whole thing, splicing runs in on both sides of
+// every hundredth of the way along.
+fn cursor_splice<D: Subject>(size: usize, _: &mut Rng) -> (Duration, usize) {
+    let mut deque = filled::<D>(size);
+    let stride = (size / 100).max(1);
+    let mut spliced = 0;
+    let elapsed = time(|| {
+        let mut cursor = deque.cursor_mut();
+        cursor.move_next();
+        let mut i = 0;
+        while cursor.current().is_some() {
+            if i % stride == 0 {
+                D::splice_around(&mut cursor);
+                for _ in 0..RUN {
+                    cursor.move_next();
+                }
+                spliced += 2;
+            }
+            cursor.move_next();
+            i += 1;
+        }
+    });
+    (elapsed, spliced)
+}
+
+fn sort<D: Subject>(size: usize, rng: &mut Rng) -> (Duration, usize) {
+    let mut deque = D::default();
+    deque.extend((0..size).map(|_| rng.next()));
+    let elapsed = time(|| deque.sort());
+    (elapsed, size)
+}
+
+fn random_access<D: Subject>(size: usize, rng: &mut Rng) -> (Duration, usize) {
+    let deque = filled::<D>(size);
+    let indexes: Vec<usize> = (0..size.min(1000))
+        .map(|_| rng.below(size as u64) as usize)
+        .collect();
+    let elapsed = time(|| indexes.iter().map(|&i| deque.get(i).unwrap()).sum::<u64>());
+    (elapsed, indexes.len())
+}
+
+fn filled<D: Subject>(size: usize) -> D {
+    let mut deque = D::default();
+    deque.extend(0..size as u64);
+    deque
+}
+
+fn time<R>(f: impl FnOnce() -> R) -> Duration {
+    let start = Instant::now();
+    black_box(f());
+    start.elapsed()
+}
+
+// xorshift, so every structure sees the same input for a given size.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+struct Record {
+    workload: &'static str,
+    structure: &'static str,
+    size: usize,
+    ops: usize,
+    best: Duration,
+    median: Duration,
+}
+
+impl Record {
+    fn ns_per_op(&self) -> f64 {
+        self.median.as_nanos() as f64 / self.ops.max(1) as f64
+    }
+}
+
+fn measure<D: Subject>(sizes: &[usize], reps: usize, records: &mut Vec<Record>) {
+    for &size in sizes {
+        for (workload, run) in workloads::<D>() {
+            let mut rng = Rng(0x2545_f491_4f6c_dd1d ^ size as u64);
+            let mut ops = 0;
+            let mut times: Vec<Duration> = (0..reps)
+                .map(|_| {
+                    let (elapsed, count) = run(size, &mut rng);
+                    ops = count;
+                    elapsed
+                })
+                .collect();
+            times.sort();
+            records.push(Record {
+                workload,
+                structure: D::NAME,
+                size,
+                ops,
+                best: times[0],
+                median: times[times.len() / 2],
+            });
+        }
+    }
+}
+
+fn print_csv(records: &[Record]) {
+    println!("workload,structure,size,ops,best_ns,median_ns,ns_per_op");
+    for record in records {
+        println!(
+            "{},{},{},{},{},{},{:.2}",
+            record.workload,
+            record.structure,
+            record.size,
+            record.ops,
+            record.best.as_nanos(),
+            record.median.as_nanos(),
+            record.ns_per_op()
+        );
+    }
+}
+
+// Median ns per operation, one row per workload and size, with how far each
+// structure is behind the fastest one on that row.
+fn print_summary(records: &[Record], structures: &[&str]) {
+    eprint!("{:<14} {:>8}", "workload", "size");
+    for structure in structures {
+        eprint!(" {:>25}", structure);
+    }
+    eprintln!();
+
+    let mut rows: Vec<(&str, usize)> = Vec::new();
+    for record in records {
+        if !rows.contains(&(record.workload, record.size)) {
+            rows.push((record.workload, record.size));
+        }
+    }
+    for (workload, size) in rows {
+        let row: Vec<&Record> = records
+            .iter()
+            .filter(|record| record.workload == workload && record.size == size)
+            .collect();
+        let fastest = row
+            .iter()
+            .map(|record| record.ns_per_op())
+            .fold(f64::INFINITY, f64::min);
+
+        eprint!("{:<14} {:>8}", workload, size);
+        for structure in structures {
+            match row.iter().find(|record| record.structure == *structure) {
+                Some(record) => {
+                    let ns = record.ns_per_op();
+                    let cell = format!("{:.1} ns ({:.1}x)", ns, ns / fastest);
+                    eprint!(" {:>25}", cell);
+                }
+                None => eprint!(" {:>25}", "-"),
+            }
+        }
+        eprintln!();
+    }
+}
+
+fn parse_args() -> Result<(Vec<usize>, usize), String> {
+    let mut sizes = vec![1_000, 10_000, 100_000];
+    let mut reps = 5;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--sizes" => {
+                sizes = value()?
+                    .split(',')
+                    .map(|size| size.parse().map_err(|_| format!("bad size: {}", size)))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--reps" => {
+                let value = value()?;
+                reps = value.parse().map_err(|_| format!("bad reps: {}", value))?;
+            }
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    if sizes.contains(&0) || reps == 0 {
+        return Err("sizes and reps have to be at least 1".to_string());
+    }
+    Ok((sizes, reps))
+}
+
+fn main() -> ExitCode {
+    let (sizes, reps) = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    if cfg!(debug_assertions) {
+        eprintln!("warning: this is a debug build, use --release for real numbers\n");
+    }
+
+    let mut records = Vec::new();
+    measure::<LinkedList<u64>>(&sizes, reps, &mut records);
+    measure::<collections::LinkedList<u64>>(&sizes, reps, &mut records);
+    measure::<VecDeque<u64>>(&sizes, reps, &mut records);
+
+    print_csv(&records);
+    print_summary(
+        &records,
+        &[
+            <LinkedList<u64> as Subject>::NAME,
+            <collections::LinkedList<u64> as Subject>::NAME,
+            <VecDeque<u64> as Subject>::NAME,
+        ],
+    );
+    ExitCode::SUCCESS
+}
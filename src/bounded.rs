@@ -0,0 +1,301 @@
+use core::fmt::{self, Debug};
+use core::mem;
+#[cfg(feature = "std")]
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+use crate::linked_list::{Iter, LinkedList};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    Reject,
+    EvictOldest,
+    EvictNewest,
+    Block,
+}
+
+// Oldest element at the front, newest at the back.
+pub struct BoundedDeque<T> {
+    list: LinkedList<T>,
+    cap: usize,
+    policy: OverflowPolicy,
+}
+
+// Needs `std` for the locking.
+#[cfg(feature = "std")]
+pub struct SyncBoundedDeque<T> {
+    shared: Arc<Shared<T>>,
+}
+
+#[cfg(feature = "std")]
+struct Shared<T> {
+    deque: Mutex<BoundedDeque<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> BoundedDeque<T> {
+    pub fn new(cap: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            list: LinkedList::new(),
+            cap,
+            policy,
+        }
+    }
+
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: OverflowPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.list.len() >= self.cap
+    }
+
+    // Pushes `elem` as the newest element. `Ok` carries whatever got evicted
+    // to make room, `Err` hands `elem` back if the policy refused it. Nothing
+    // else can make room while we hold `&mut self`, so `Block` refuses too.
+    pub fn push(&mut self, elem: T) -> Result<Option<T>, T> {
+        if !self.is_full() {
+            self.list.push_back(elem);
+            return Ok(None);
+        }
+
+        match self.policy {
+            OverflowPolicy::Reject | OverflowPolicy::Block => Err(elem),
+            OverflowPolicy::EvictOldest => {
+                self.list.push_back(elem);
+                Ok(self.list.pop_front())
+            }
+            OverflowPolicy::EvictNewest => match self.list.pop_back() {
+                Some(newest) => {
+                    self.list.push_back(elem);
+                    Ok(Some(newest))
+                }
+                None => Ok(Some(elem)),
+            },
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.list.front_mut()
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.list.back_mut()
+    }
+
+    // Changes the bound, returning the excess in order. `EvictNewest` gives
+    // up the newest elements, every other policy the oldest.
+    pub fn set_capacity(&mut self, cap: usize) -> LinkedList<T> {
+        self.cap = cap;
+        let len = self.list.len();
+        if len <= cap {
+            return LinkedList::new();
+        }
+
+        match self.policy {
+            OverflowPolicy::EvictNewest => self.list.split_off(cap),
+            _ => {
+                let kept = self.list.split_off(len - cap);
+                mem::replace(&mut self.list, kept)
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    pub fn as_list(&self) -> &LinkedList<T> {
+        &self.list
+    }
+
+    pub fn into_list(self) -> LinkedList<T> {
+        self.list
+    }
+}
+
+impl<T: Debug> Debug for BoundedDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedDeque<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> SyncBoundedDeque<T> {
+    pub fn new(cap: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                deque: Mutex::new(BoundedDeque::new(cap, policy)),
+                not_empty: Condvar::new(),
+                not_full: Condvar::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BoundedDeque<T>> {
+        self.shared
+            .deque
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn cap(&self) -> usize {
+        self.lock().cap()
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.lock().policy()
+    }
+
+    pub fn set_policy(&self, policy: OverflowPolicy) {
+        self.lock().set_policy(policy);
+        // Blocked pushers have to re-check what they're meant to do now.
+        self.shared.not_full.notify_all();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    // Like `BoundedDeque::push`, except `Block` waits for room instead of
+    // refusing. At capacity 0 there will never be room, so `elem` comes
+    // straight back, including to pushers already waiting when the capacity
+    // drops to 0.
+    pub fn push(&self, elem: T) -> Result<Option<T>, T> {
+        let mut deque = self.lock();
+        while deque.policy == OverflowPolicy::Block && deque.is_full() && deque.cap > 0 {
+            deque = self
+                .shared
+                .not_full
+                .wait(deque)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+
+        let result = deque.push(elem);
+        drop(deque);
+        if result.is_ok() {
+            self.shared.not_empty.notify_one();
+        }
+        result
+    }
+
+    pub fn try_push(&self, elem: T) -> Result<Option<T>, T> {
+        let result = self.lock().push(elem);
+        if result.is_ok() {
+            self.shared.not_empty.notify_one();
+        }
+        result
+    }
+
+    pub fn pop_front(&self) -> Option<T> {
+        let elem = self.lock().pop_front();
+        if elem.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        elem
+    }
+
+    pub fn pop_back(&self) -> Option<T> {
+        let elem = self.lock().pop_back();
+        if elem.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        elem
+    }
+
+    // Waits for the oldest element.
+    pub fn take(&self) -> T {
+        let mut deque = self.lock();
+        let elem = loop {
+            if let Some(elem) = deque.pop_front() {
+                break elem;
+            }
+            deque = self
+                .shared
+                .not_empty
+                .wait(deque)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        };
+
+        drop(deque);
+        self.shared.not_full.notify_one();
+        elem
+    }
+
+    pub fn set_capacity(&self, cap: usize) -> LinkedList<T> {
+        let evicted = self.lock().set_capacity(cap);
+        self.shared.not_full.notify_all();
+        evicted
+    }
+
+    pub fn drain(&self) -> LinkedList<T> {
+        let drained = mem::take(&mut self.lock().list);
+        self.shared.not_full.notify_all();
+        drained
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Clone for SyncBoundedDeque<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Debug> Debug for SyncBoundedDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.lock().fmt(f)
+    }
+}
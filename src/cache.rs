@@ -0,0 +1,55 @@
+pub mod arc;
+pub mod lfu;
+pub mod slru;
+
+pub trait Cache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V>;
+
+    fn put(&mut self, key: K, value: V) -> Option<V>;
+
+    fn len(&self) -> usize;
+
+    fn capacity(&self) -> usize;
+
+    fn stats(&self) -> CacheStats;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    pub fn requests(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    pub fn hit_ratio(&self) -> f64 {
+        if self.requests() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.requests() as f64
+        }
+    }
+}
+
+pub fn replay<K, V, C, I, F>(cache: &mut C, trace: I, mut load: F) -> CacheStats
+where
+    C: Cache<K, V> + ?Sized,
+    I: IntoIterator<Item = K>,
+    F: FnMut(&K) -> V,
+{
+    for key in trace {
+        if cache.get(&key).is_none() {
+            let value = load(&key);
+            cache.put(key, value);
+        }
+    }
+    cache.stats()
+}
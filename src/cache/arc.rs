@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::cache::{Cache, CacheStats};
+use crate::linked_list::{LinkedList, NodeRef};
+
+enum Slot<K, V> {
+    Recent(NodeRef<(K, V)>),
+    Frequent(NodeRef<(K, V)>),
+    RecentGhost(NodeRef<K>),
+    FrequentGhost(NodeRef<K>),
+}
+
+// Adaptive Replacement Cache (Megiddo & Modha). `recent` and `frequent` hold
+// the cached entries seen once and more than once, the ghost lists remember
+// keys recently evicted from each. A miss that hits a ghost list means that
+// side was evicted too eagerly, and moves `target`, the share of the capacity
+// `recent` is allowed to keep, in its favour. All lists are most recent first.
+pub struct ArcCache<K, V> {
+    recent: LinkedList<(K, V)>,
+    frequent: LinkedList<(K, V)>,
+    recent_ghosts: LinkedList<K>,
+    frequent_ghosts: LinkedList<K>,
+    map: HashMap<K, Slot<K, V>>,
+    target: usize,
+    cap: usize,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone, V> ArcCache<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            recent: LinkedList::new(),
+            frequent: LinkedList::new(),
+            recent_ghosts: LinkedList::new(),
+            frequent_ghosts: LinkedList::new(),
+            map: HashMap::new(),
+            target: 0,
+            cap,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        matches!(
+            self.map.get(key),
+            Some(Slot::Recent(_)) | Some(Slot::Frequent(_))
+        )
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    fn hit(&mut self, key: &K) -> Option<&mut V> {
+        let slot = self.map.get_mut(key)?;
+
+        unsafe {
+            let node = match *slot {
+                Slot::Recent(node) => {
+                    self.recent.unlink_node(node);
+                    self.frequent.link_front(node);
+                    *slot = Slot::Frequent(node);
+                    node
+                }
+                Slot::Frequent(node) => {
+                    self.frequent.move_to_front(node);
+                    node
+                }
+                _ => return None,
+            };
+            Some(&mut self.frequent.node_mut(node).1)
+        }
+    }
+
+    fn replace(&mut self, in_frequent_ghosts: bool) {
+        if self.len() < self.cap {
+            return;
+        }
+
+        let recent = self.recent.len();
+        let from_recent = recent > 0
+            && (self.frequent.is_empty()
+                || recent > self.target
+                || (in_frequent_ghosts && recent == self.target));
+
+        if from_recent {
+            let (key, _) = self.recent.pop_back().unwrap();
+            let ghost = self.recent_ghosts.push_front_node(key.clone());
+            *self.map.get_mut(&key).unwrap() = Slot::RecentGhost(ghost);
+        } else {
+            let (key, _) = self.frequent.pop_back().unwrap();
+            let ghost = self.frequent_ghosts.push_front_node(key.clone());
+            *self.map.get_mut(&key).unwrap() = Slot::FrequentGhost(ghost);
+        }
+        self.stats.evictions += 1;
+    }
+
+    fn forget_oldest(&mut self, frequent: bool) {
+        let ghosts = if frequent {
+            &mut self.frequent_ghosts
+        } else {
+            &mut self.recent_ghosts
+        };
+
+        if let Some(key) = ghosts.pop_back() {
+            self.map.remove(&key);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for ArcCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if self.contains(key) {
+            self.stats.hits += 1;
+            self.hit(key).map(|value| &*value)
+        } else {
+            self.stats.misses += 1;
+            None
+        }
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.hit(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        if self.cap == 0 {
+            return None;
+        }
+
+        match self.map.get(&key) {
+            Some(&Slot::RecentGhost(ghost)) => {
+                let step = (self.frequent_ghosts.len() / self.recent_ghosts.len()).max(1);
+                self.target = (self.target + step).min(self.cap);
+                self.replace(false);
+                unsafe { self.recent_ghosts.remove_node(ghost) };
+            }
+            Some(&Slot::FrequentGhost(ghost)) => {
+                let step = (self.recent_ghosts.len() / self.frequent_ghosts.len()).max(1);
+                self.target = self.target.saturating_sub(step);
+                self.replace(true);
+                unsafe { self.frequent_ghosts.remove_node(ghost) };
+            }
+            _ => {
+                let recent_side = self.recent.len() + self.recent_ghosts.len();
+                let total = recent_side + self.frequent.len() + self.frequent_ghosts.len();
+
+                if recent_side == self.cap {
+                    if self.recent.len() < self.cap {
+                        self.forget_oldest(false);
+                        self.replace(false);
+                    } else {
+                        let (old, _) = self.recent.pop_back().unwrap();
+                        self.map.remove(&old);
+                        self.stats.evictions += 1;
+                    }
+                } else if total >= self.cap {
+                    if total == 2 * self.cap {
+                        self.forget_oldest(true);
+                    }
+                    self.replace(false);
+                }
+
+                let node = self.recent.push_front_node((key.clone(), value));
+                self.map.insert(key, Slot::Recent(node));
+                return None;
+            }
+        }
+
+        // Coming back from a ghost list means it's been seen before, so it
+        // goes straight to the frequent side.
+        let node = self.frequent.push_front_node((key.clone(), value));
+        self.map.insert(key, Slot::Frequent(node));
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.recent.len() + self.frequent.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
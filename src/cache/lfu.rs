@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::cache::{Cache, CacheStats};
+use crate::linked_list::{LinkedList, NodeRef};
+
+// One list per access count, most recent first within each, so both finding
+// the victim and bumping an entry's count are O(1). Ties between equally
+// frequent entries go to the least recently used one.
+pub struct LfuCache<K, V> {
+    buckets: HashMap<u64, LinkedList<(K, V)>>,
+    map: HashMap<K, (u64, NodeRef<(K, V)>)>,
+    min_freq: u64,
+    cap: usize,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            map: HashMap::new(),
+            min_freq: 0,
+            cap,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn frequency(&self, key: &K) -> Option<u64> {
+        self.map.get(key).map(|&(freq, _)| freq)
+    }
+
+    fn touch(&mut self, key: &K) -> Option<&mut V> {
+        let (freq, node) = self.map.get_mut(key)?;
+        let node = *node;
+        let old = *freq;
+        *freq += 1;
+
+        unsafe {
+            let bucket = self.buckets.get_mut(&old).unwrap();
+            bucket.unlink_node(node);
+            if bucket.is_empty() {
+                self.buckets.remove(&old);
+                if self.min_freq == old {
+                    self.min_freq = old + 1;
+                }
+            }
+
+            let bucket = self.buckets.entry(old + 1).or_default();
+            bucket.link_front(node);
+            Some(&mut bucket.node_mut(node).1)
+        }
+    }
+
+    fn evict(&mut self) {
+        let bucket = self.buckets.get_mut(&self.min_freq).unwrap();
+        let (key, _) = bucket.pop_back().unwrap();
+        if bucket.is_empty() {
+            self.buckets.remove(&self.min_freq);
+        }
+        self.map.remove(&key);
+        self.stats.evictions += 1;
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for LfuCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if self.map.contains_key(key) {
+            self.stats.hits += 1;
+            self.touch(key).map(|value| &*value)
+        } else {
+            self.stats.misses += 1;
+            None
+        }
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.touch(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        if self.cap == 0 {
+            return None;
+        }
+        if self.map.len() == self.cap {
+            self.evict();
+        }
+
+        let node = self
+            .buckets
+            .entry(1)
+            .or_default()
+            .push_front_node((key.clone(), value));
+        self.map.insert(key, (1, node));
+        self.min_freq = 1;
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
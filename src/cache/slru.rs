@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::cache::{Cache, CacheStats};
+use crate::linked_list::{LinkedList, NodeRef};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    Probation,
+    Protected,
+}
+
+// New entries start out on probation and only get into the protected segment
+// on their second hit, so a one-off scan can't push out the working set.
+pub struct SlruCache<K, V> {
+    probation: LinkedList<(K, V)>,
+    protected: LinkedList<(K, V)>,
+    map: HashMap<K, (Segment, NodeRef<(K, V)>)>,
+    cap: usize,
+    protected_cap: usize,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone, V> SlruCache<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Self::with_protected(cap, cap - cap / 5)
+    }
+
+    pub fn with_protected(cap: usize, protected_cap: usize) -> Self {
+        assert!(
+            protected_cap <= cap,
+            "protected segment (is {}) should be <= capacity (is {})",
+            protected_cap,
+            cap
+        );
+
+        Self {
+            probation: LinkedList::new(),
+            protected: LinkedList::new(),
+            map: HashMap::new(),
+            cap,
+            protected_cap,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    fn segment_mut(&mut self, segment: Segment) -> &mut LinkedList<(K, V)> {
+        match segment {
+            Segment::Probation => &mut self.probation,
+            Segment::Protected => &mut self.protected,
+        }
+    }
+
+    fn promote(&mut self, key: &K) -> Option<&mut V> {
+        let (segment, node) = self.map.get_mut(key)?;
+        let node = *node;
+
+        unsafe {
+            if *segment == Segment::Protected {
+                self.protected.move_to_front(node);
+                return Some(&mut self.protected.node_mut(node).1);
+            }
+
+            *segment = Segment::Protected;
+            self.probation.unlink_node(node);
+            self.protected.link_front(node);
+
+            if self.protected.len() > self.protected_cap {
+                let demoted = self.protected.back_node().unwrap();
+                self.protected.unlink_node(demoted);
+                self.probation.link_front(demoted);
+                let demoted_key = &self.probation.node(demoted).0;
+                self.map.get_mut(demoted_key).unwrap().0 = Segment::Probation;
+            }
+
+            // With a tiny protected segment the entry that was just promoted
+            // can be the one that got demoted again.
+            let segment = self.map[key].0;
+            Some(&mut self.segment_mut(segment).node_mut(node).1)
+        }
+    }
+
+    fn evict(&mut self) {
+        let entry = match self.probation.pop_back() {
+            Some(entry) => entry,
+            None => self.protected.pop_back().unwrap(),
+        };
+        self.map.remove(&entry.0);
+        self.stats.evictions += 1;
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for SlruCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        if self.map.contains_key(key) {
+            self.stats.hits += 1;
+            self.promote(key).map(|value| &*value)
+        } else {
+            self.stats.misses += 1;
+            None
+        }
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.promote(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+
+        if self.cap == 0 {
+            return None;
+        }
+        if self.map.len() == self.cap {
+            self.evict();
+        }
+
+        let node = self.probation.push_front_node((key.clone(), value));
+        self.map.insert(key, (Segment::Probation, node));
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::Deref;
+
+use crate::linked_list::{CursorMut, Iter, IterMut, LinkedList};
+
+pub struct CowList<T> {
+    list: Arc<LinkedList<T>>,
+}
+
+impl<T> CowList<T> {
+    pub fn new() -> Self {
+        Self {
+            list: Arc::new(LinkedList::new()),
+        }
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.list, &other.list)
+    }
+
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.list) == 1
+    }
+
+    pub fn clear(&mut self) {
+        // Nothing to preserve, so a shared list is just let go of instead of
+        // being copied only to be emptied.
+        match Arc::get_mut(&mut self.list) {
+            Some(list) => list.clear(),
+            None => self.list = Arc::new(LinkedList::new()),
+        }
+    }
+}
+
+impl<T: Clone> CowList<T> {
+    pub fn make_mut(&mut self) -> &mut LinkedList<T> {
+        Arc::make_mut(&mut self.list)
+    }
+
+    pub fn into_inner(self) -> LinkedList<T> {
+        Arc::try_unwrap(self.list).unwrap_or_else(|shared| (*shared).clone())
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        self.make_mut().push_front(elem)
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        self.make_mut().push_back(elem)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.make_mut().pop_front()
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.make_mut().pop_back()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.make_mut().front_mut()
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.make_mut().back_mut()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        self.make_mut().iter_mut()
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        self.make_mut().cursor_mut()
+    }
+}
+
+impl<T> Deref for CowList<T> {
+    type Target = LinkedList<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.list
+    }
+}
+
+impl<T> Clone for CowList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            list: Arc::clone(&self.list),
+        }
+    }
+}
+
+impl<T> Default for CowList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<LinkedList<T>> for CowList<T> {
+    fn from(list: LinkedList<T>) -> Self {
+        Self {
+            list: Arc::new(list),
+        }
+    }
+}
+
+impl<T> FromIterator<T> for CowList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<LinkedList<T>>())
+    }
+}
+
+impl<T: Debug> Debug for CowList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.list.fmt(f)
+    }
+}
+
+impl<T: PartialEq> PartialEq for CowList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.list == other.list
+    }
+}
+
+impl<T: Eq> Eq for CowList<T> {}
+
+impl<T: Hash> Hash for CowList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.list.hash(state)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CowList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
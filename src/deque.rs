@@ -0,0 +1,428 @@
+use alloc::collections::{self, VecDeque};
+use core::mem;
+
+use crate::linked_list::{self, LinkedList};
+
+pub mod algorithms;
+
+pub trait Deque<T>: Default + Extend<T> {
+    type Iter<'a>: DoubleEndedIterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn push_front(&mut self, elem: T);
+
+    fn push_back(&mut self, elem: T);
+
+    fn pop_front(&mut self) -> Option<T>;
+
+    fn pop_back(&mut self) -> Option<T>;
+
+    fn front(&self) -> Option<&T>;
+
+    fn back(&self) -> Option<&T>;
+
+    fn front_mut(&mut self) -> Option<&mut T>;
+
+    fn back_mut(&mut self) -> Option<&mut T>;
+
+    fn len(&self) -> usize;
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn clear(&mut self);
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub trait SplittableDeque<T>: Deque<T> {
+    fn split_off(&mut self, at: usize) -> Self;
+
+    fn append(&mut self, other: &mut Self);
+}
+
+pub trait CursorDeque<T>: Deque<T> {
+    type CursorMut<'a>: DequeCursor<T>
+    where
+        Self: 'a;
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_>;
+}
+
+// The part of `linked_list::CursorMut` every deque can offer, ghost included.
+pub trait DequeCursor<T> {
+    fn index(&self) -> Option<usize>;
+
+    fn move_next(&mut self);
+
+    fn move_prev(&mut self);
+
+    fn current(&mut self) -> Option<&mut T>;
+
+    fn insert_before(&mut self, elem: T);
+
+    fn insert_after(&mut self, elem: T);
+
+    fn remove_current(&mut self) -> Option<T>;
+}
+
+// A cursor for deques without one of their own. The deque is taken apart
+// into what's before the cursor and what's from it on, so moving is just
+// popping off one and pushing onto the other. On the ghost everything is in
+// `after`. Dropping the cursor puts the deque back together.
+pub struct SplitCursor<'a, T, D: SplittableDeque<T>> {
+    deque: &'a mut D,
+    before: D,
+    after: D,
+    index: Option<usize>,
+    _elem: core::marker::PhantomData<T>,
+}
+
+impl<T> Deque<T> for LinkedList<T> {
+    type Iter<'a>
+        = linked_list::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn push_front(&mut self, elem: T) {
+        LinkedList::push_front(self, elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        LinkedList::push_back(self, elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        LinkedList::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        LinkedList::pop_back(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        LinkedList::front(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        LinkedList::back(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        LinkedList::front_mut(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        LinkedList::back_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        LinkedList::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        LinkedList::iter(self)
+    }
+
+    fn clear(&mut self) {
+        LinkedList::clear(self)
+    }
+}
+
+impl<T> SplittableDeque<T> for LinkedList<T> {
+    fn split_off(&mut self, at: usize) -> Self {
+        LinkedList::split_off(self, at)
+    }
+
+    fn append(&mut self, other: &mut Self) {
+        LinkedList::append(self, other)
+    }
+}
+
+impl<T> CursorDeque<T> for LinkedList<T> {
+    type CursorMut<'a>
+        = linked_list::CursorMut<'a, T>
+    where
+        T: 'a;
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        LinkedList::cursor_mut(self)
+    }
+}
+
+impl<'a, T> DequeCursor<T> for linked_list::CursorMut<'a, T> {
+    fn index(&self) -> Option<usize> {
+        linked_list::CursorMut::index(self)
+    }
+
+    fn move_next(&mut self) {
+        linked_list::CursorMut::move_next(self)
+    }
+
+    fn move_prev(&mut self) {
+        linked_list::CursorMut::move_prev(self)
+    }
+
+    fn current(&mut self) -> Option<&mut T> {
+        linked_list::CursorMut::current(self)
+    }
+
+    fn insert_before(&mut self, elem: T) {
+        linked_list::CursorMut::insert_before(self, elem)
+    }
+
+    fn insert_after(&mut self, elem: T) {
+        linked_list::CursorMut::insert_after(self, elem)
+    }
+
+    fn remove_current(&mut self) -> Option<T> {
+        linked_list::CursorMut::remove_current(self)
+    }
+}
+
+impl<T> Deque<T> for VecDeque<T> {
+    type Iter<'a>
+        = alloc::collections::vec_deque::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn push_front(&mut self, elem: T) {
+        VecDeque::push_front(self, elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        VecDeque::push_back(self, elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecDeque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        VecDeque::pop_back(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        VecDeque::front(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        VecDeque::back(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        VecDeque::front_mut(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        VecDeque::back_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        VecDeque::iter(self)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self)
+    }
+}
+
+impl<T> SplittableDeque<T> for VecDeque<T> {
+    fn split_off(&mut self, at: usize) -> Self {
+        VecDeque::split_off(self, at)
+    }
+
+    fn append(&mut self, other: &mut Self) {
+        VecDeque::append(self, other)
+    }
+}
+
+impl<T> CursorDeque<T> for VecDeque<T> {
+    type CursorMut<'a>
+        = SplitCursor<'a, T, Self>
+    where
+        T: 'a;
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SplitCursor::new(self)
+    }
+}
+
+impl<T> Deque<T> for collections::LinkedList<T> {
+    type Iter<'a>
+        = collections::linked_list::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn push_front(&mut self, elem: T) {
+        collections::LinkedList::push_front(self, elem)
+    }
+
+    fn push_back(&mut self, elem: T) {
+        collections::LinkedList::push_back(self, elem)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        collections::LinkedList::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        collections::LinkedList::pop_back(self)
+    }
+
+    fn front(&self) -> Option<&T> {
+        collections::LinkedList::front(self)
+    }
+
+    fn back(&self) -> Option<&T> {
+        collections::LinkedList::back(self)
+    }
+
+    fn front_mut(&mut self) -> Option<&mut T> {
+        collections::LinkedList::front_mut(self)
+    }
+
+    fn back_mut(&mut self) -> Option<&mut T> {
+        collections::LinkedList::back_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        collections::LinkedList::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        collections::LinkedList::iter(self)
+    }
+
+    fn clear(&mut self) {
+        collections::LinkedList::clear(self)
+    }
+}
+
+impl<T> SplittableDeque<T> for collections::LinkedList<T> {
+    fn split_off(&mut self, at: usize) -> Self {
+        collections::LinkedList::split_off(self, at)
+    }
+
+    fn append(&mut self, other: &mut Self) {
+        collections::LinkedList::append(self, other)
+    }
+}
+
+impl<T> CursorDeque<T> for collections::LinkedList<T> {
+    type CursorMut<'a>
+        = SplitCursor<'a, T, Self>
+    where
+        T: 'a;
+
+    fn cursor_mut(&mut self) -> Self::CursorMut<'_> {
+        SplitCursor::new(self)
+    }
+}
+
+impl<'a, T, D: SplittableDeque<T>> SplitCursor<'a, T, D> {
+    pub fn new(deque: &'a mut D) -> Self {
+        let after = mem::take(deque);
+        Self {
+            deque,
+            before: D::default(),
+            after,
+            index: None,
+            _elem: core::marker::PhantomData,
+        }
+    }
+
+    // Going from the last element to the ghost or the other way round moves
+    // everything across at once, which is just a swap.
+    fn move_to_ghost(&mut self) {
+        self.before.append(&mut self.after);
+        mem::swap(&mut self.before, &mut self.after);
+        self.index = None;
+    }
+}
+
+impl<'a, T, D: SplittableDeque<T>> DequeCursor<T> for SplitCursor<'a, T, D> {
+    fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    fn move_next(&mut self) {
+        match self.index {
+            Some(index) if self.after.len() > 1 => {
+                let elem = self.after.pop_front().unwrap();
+                self.before.push_back(elem);
+                self.index = Some(index + 1);
+            }
+            Some(_) => self.move_to_ghost(),
+            None if !self.after.is_empty() => self.index = Some(0),
+            None => {}
+        }
+    }
+
+    fn move_prev(&mut self) {
+        match self.index {
+            Some(0) => self.index = None,
+            Some(index) => {
+                let elem = self.before.pop_back().unwrap();
+                self.after.push_front(elem);
+                self.index = Some(index - 1);
+            }
+            None if !self.after.is_empty() => {
+                mem::swap(&mut self.before, &mut self.after);
+                let elem = self.before.pop_back().unwrap();
+                self.after.push_front(elem);
+                self.index = Some(self.before.len());
+            }
+            None => {}
+        }
+    }
+
+    fn current(&mut self) -> Option<&mut T> {
+        self.index?;
+        self.after.front_mut()
+    }
+
+    fn insert_before(&mut self, elem: T) {
+        match self.index {
+            Some(index) => {
+                self.before.push_back(elem);
+                self.index = Some(index + 1);
+            }
+            None => self.after.push_back(elem),
+        }
+    }
+
+    fn insert_after(&mut self, elem: T) {
+        match self.index {
+            Some(_) => {
+                let current = self.after.pop_front().unwrap();
+                self.after.push_front(elem);
+                self.after.push_front(current);
+            }
+            None => self.after.push_front(elem),
+        }
+    }
+
+    fn remove_current(&mut self) -> Option<T> {
+        self.index?;
+        let elem = self.after.pop_front();
+        if self.after.is_empty() {
+            self.move_to_ghost();
+        }
+        elem
+    }
+}
+
+impl<'a, T, D: SplittableDeque<T>> Drop for SplitCursor<'a, T, D> {
+    fn drop(&mut self) {
+        self.before.append(&mut self.after);
+        mem::swap(self.deque, &mut self.before);
+    }
+}
@@ -0,0 +1,161 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::mem;
+
+use super::{Deque, SplittableDeque};
+use crate::window::{MonotonicDeque, Reversed};
+
+// Moves the first `n` elements to the back, from whichever end is shorter.
+pub fn rotate_left<T, D: Deque<T>>(deque: &mut D, n: usize) {
+    let len = deque.len();
+    if len == 0 {
+        return;
+    }
+
+    let n = n % len;
+    if n <= len / 2 {
+        for _ in 0..n {
+            let elem = deque.pop_front().unwrap();
+            deque.push_back(elem);
+        }
+    } else {
+        for _ in n..len {
+            let elem = deque.pop_back().unwrap();
+            deque.push_front(elem);
+        }
+    }
+}
+
+pub fn rotate_right<T, D: Deque<T>>(deque: &mut D, n: usize) {
+    let len = deque.len();
+    if len > 0 {
+        rotate_left(deque, len - n % len);
+    }
+}
+
+// Same as `rotate_left`, but as one split and one append, which is cheaper
+// for the linked lists than moving elements one at a time.
+pub fn rotate_left_split<T, D: SplittableDeque<T>>(deque: &mut D, n: usize) {
+    let len = deque.len();
+    if len == 0 {
+        return;
+    }
+
+    let mut tail = deque.split_off(n % len);
+    tail.append(deque);
+    *deque = tail;
+}
+
+// Calls `f` with every run of `size` consecutive items, oldest first.
+pub fn windows<T, D, I, F>(iter: I, size: usize, mut f: F)
+where
+    D: Deque<T>,
+    I: IntoIterator<Item = T>,
+    F: FnMut(&D),
+{
+    assert!(size > 0, "window size must be non-zero");
+
+    let mut window = D::default();
+    for item in iter {
+        if window.len() == size {
+            window.pop_front();
+        }
+        window.push_back(item);
+        if window.len() == size {
+            f(&window);
+        }
+    }
+}
+
+// The greatest item by `cmp` in every window of `size`, from a
+// `MonotonicDeque` backed by `D`, so each item is pushed and popped once.
+// Ties go to the later item.
+pub fn sliding_max_by<T, D, I, F>(iter: I, size: usize, cmp: F) -> Vec<T>
+where
+    T: Clone,
+    D: Deque<(usize, T)>,
+    I: IntoIterator<Item = T>,
+    F: Fn(&T, &T) -> Ordering,
+{
+    assert!(size > 0, "window size must be non-zero");
+
+    let mut window = MonotonicDeque::with_deque(Reversed(cmp), D::default());
+    let mut best = Vec::new();
+    for item in iter {
+        let index = window.push(item);
+        window.expire((index + 1).saturating_sub(size));
+        if index + 1 >= size {
+            best.push(window.best().unwrap().clone());
+        }
+    }
+    best
+}
+
+pub fn sliding_max<T, D, I>(iter: I, size: usize) -> Vec<T>
+where
+    T: Ord + Clone,
+    D: Deque<(usize, T)>,
+    I: IntoIterator<Item = T>,
+{
+    sliding_max_by::<_, D, _, _>(iter, size, T::cmp)
+}
+
+pub fn sliding_min<T, D, I>(iter: I, size: usize) -> Vec<T>
+where
+    T: Ord + Clone,
+    D: Deque<(usize, T)>,
+    I: IntoIterator<Item = T>,
+{
+    sliding_max_by::<_, D, _, _>(iter, size, |a, b| b.cmp(a))
+}
+
+// Merges two deques sorted by `cmp` into one. Stable: on ties, `a` goes first.
+pub fn merge_by<T, D, F>(mut a: D, mut b: D, mut cmp: F) -> D
+where
+    D: Deque<T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let mut merged = D::default();
+    loop {
+        let from_a = match (a.front(), b.front()) {
+            (Some(x), Some(y)) => cmp(x, y) != Ordering::Greater,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => return merged,
+        };
+        let elem = if from_a { a.pop_front() } else { b.pop_front() };
+        merged.push_back(elem.unwrap());
+    }
+}
+
+pub fn merge<T: Ord, D: Deque<T>>(a: D, b: D) -> D {
+    merge_by(a, b, T::cmp)
+}
+
+// Top-down merge sort built on `split_off`, stable like `merge_by`.
+pub fn sort_by<T, D, F>(deque: &mut D, mut cmp: F)
+where
+    D: SplittableDeque<T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    sort_by_inner(deque, &mut cmp);
+}
+
+pub fn sort<T: Ord, D: SplittableDeque<T>>(deque: &mut D) {
+    sort_by(deque, T::cmp);
+}
+
+fn sort_by_inner<T, D, F>(deque: &mut D, cmp: &mut F)
+where
+    D: SplittableDeque<T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    if deque.len() < 2 {
+        return;
+    }
+
+    let mut back = deque.split_off(deque.len() / 2);
+    sort_by_inner(deque, cmp);
+    sort_by_inner(&mut back, cmp);
+    *deque = merge_by(mem::take(deque), back, &mut *cmp);
+}
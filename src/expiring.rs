@@ -0,0 +1,244 @@
+use std::fmt::{self, Debug};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::linked_list::{self, LinkedList};
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Only moves when told to. Clones share the same time, so a test can keep one
+// and hand the other to the deque.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+}
+
+// Every element gets the same TTL and the clock never goes backwards, so
+// deadlines only grow from front to back and whatever has expired is always
+// a prefix. Expired elements stay put until something with `&mut self`
+// drops them, everything else just looks past them.
+//
+// A deadline too far out for `Instant` is `None` and never comes. Everything
+// pushed after it overflows too, so those stay at the back.
+pub struct ExpiringDeque<T, C = SystemClock> {
+    list: LinkedList<(Option<Instant>, T)>,
+    ttl: Duration,
+    clock: C,
+}
+
+pub struct PopExpired<'a, T> {
+    list: &'a mut LinkedList<(Option<Instant>, T)>,
+    now: Instant,
+}
+
+pub struct Iter<'a, T> {
+    iter: linked_list::Iter<'a, (Option<Instant>, T)>,
+}
+
+fn expired(deadline: Option<Instant>, now: Instant) -> bool {
+    deadline.is_some_and(|deadline| deadline <= now)
+}
+
+impl<T> ExpiringDeque<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_clock(ttl, SystemClock)
+    }
+}
+
+impl<T, C: Clock> ExpiringDeque<T, C> {
+    pub fn with_clock(ttl: Duration, clock: C) -> Self {
+        Self {
+            list: LinkedList::new(),
+            ttl,
+            clock,
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    // Counts expired elements that haven't been dropped yet.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    // `None` if it never expires.
+    pub fn push_back(&mut self, elem: T) -> Option<Instant> {
+        let deadline = self.clock.now().checked_add(self.ttl);
+        self.list.push_back((deadline, elem));
+        deadline
+    }
+
+    pub fn pop_expired(&mut self) -> PopExpired<'_, T> {
+        PopExpired {
+            now: self.clock.now(),
+            list: &mut self.list,
+        }
+    }
+
+    pub fn purge(&mut self) -> usize {
+        self.pop_expired().count()
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.list.front().and_then(|&(deadline, _)| deadline)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.purge();
+        self.list.pop_front().map(|(_, elem)| elem)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let now = self.clock.now();
+        match self.list.pop_back() {
+            Some((deadline, elem)) if !expired(deadline, now) => Some(elem),
+            // The newest one expired, so did everything else.
+            Some(_) => {
+                self.list.clear();
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        let now = self.clock.now();
+        self.list
+            .back()
+            .filter(|&&(deadline, _)| !expired(deadline, now))
+            .map(|(_, elem)| elem)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let now = self.clock.now();
+        // Expired elements are only ever a prefix, so this stops at the first
+        // live one.
+        let expired = self
+            .list
+            .iter()
+            .take_while(|&&(deadline, _)| expired(deadline, now))
+            .count();
+
+        let mut iter = self.list.iter();
+        if expired > 0 {
+            iter.nth(expired - 1);
+        }
+        Iter { iter }
+    }
+
+    pub fn contains(&self, elem: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|live| live == elem)
+    }
+
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<&T> {
+        self.iter().find(|&elem| pred(elem))
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+}
+
+impl<T: Debug, C: Clock> Debug for ExpiringDeque<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, C: Clock> IntoIterator for &'a ExpiringDeque<T, C> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for PopExpired<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self.list.front() {
+            Some(&(deadline, _)) if expired(deadline, self.now) => {
+                self.list.pop_front().map(|(_, elem)| elem)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, elem)| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, elem)| elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
@@ -0,0 +1,896 @@
+use alloc::boxed::Box;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr::NonNull;
+
+const MAX_LEVEL: usize = 32;
+
+// A skip list indexed by position. Every node is linked both ways on each of
+// its levels, and each forward link knows how many positions it skips.
+//
+// The ends are kept apart from the nodes: for every level, `ends` has the
+// first and last node on it along with their "coordinates", where a node's
+// position is its coordinate minus `base`. Pushing or popping at the front
+// only moves `base`, so neither end op has to touch the levels above the
+// node's own height, which makes them O(1) expected. A link into the back
+// never needs a span, since no search ever has to step past the last node.
+pub struct IndexedList<T> {
+    ends: [End<T>; MAX_LEVEL],
+    height: usize,
+    base: isize,
+    len: usize,
+    seed: u64,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    levels: Box<[Level<T>]>,
+    elem: T,
+}
+
+struct Level<T> {
+    prev: Link<T>,
+    next: Link<T>,
+    span: usize,
+}
+
+struct End<T> {
+    first: Link<T>,
+    last: Link<T>,
+    first_coord: isize,
+    last_coord: isize,
+}
+
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T> {
+    list: IndexedList<T>,
+}
+
+pub struct CursorMut<'a, T> {
+    list: &'a mut IndexedList<T>,
+    cursor: Link<T>,
+    index: Option<usize>,
+}
+
+// Odd, so xorshift never gets stuck at 0. Without `std` there's nothing to
+// draw entropy from and every list starts from the same seed, which only
+// matters against input built to unbalance it.
+pub(crate) fn random_seed() -> u64 {
+    #[cfg(feature = "std")]
+    {
+        use std::collections::hash_map::RandomState;
+        use std::hash::BuildHasher;
+
+        RandomState::new().build_hasher().finish() | 1
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        0x9e37_79b9_7f4a_7c15
+    }
+}
+
+impl<T> IndexedList<T> {
+    pub fn new() -> Self {
+        Self {
+            ends: core::array::from_fn(|_| End::empty()),
+            height: 0,
+            base: 0,
+            len: 0,
+            seed: random_seed(),
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.ends[0].first.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.ends[0].first.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.ends[0].last.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.ends[0].last.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        unsafe {
+            let new = self.new_node(elem);
+            self.base -= 1;
+            let coord = self.base;
+
+            let levels = &mut (*new.as_ptr()).levels;
+            for (level, end) in self.ends.iter_mut().enumerate() {
+                let Some(link) = levels.get_mut(level) else {
+                    break;
+                };
+
+                link.next = end.first;
+                match end.first {
+                    Some(next) => {
+                        link.span = (end.first_coord - coord) as usize;
+                        (*next.as_ptr()).levels[level].prev = Some(new);
+                    }
+                    None => {
+                        end.last = Some(new);
+                        end.last_coord = coord;
+                    }
+                }
+                end.first = Some(new);
+                end.first_coord = coord;
+            }
+
+            self.len += 1;
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let new = self.new_node(elem);
+            let coord = self.base + self.len as isize;
+
+            let levels = &mut (*new.as_ptr()).levels;
+            for (level, end) in self.ends.iter_mut().enumerate() {
+                let Some(link) = levels.get_mut(level) else {
+                    break;
+                };
+
+                link.prev = end.last;
+                match end.last {
+                    Some(prev) => {
+                        let prev = &mut (*prev.as_ptr()).levels[level];
+                        prev.next = Some(new);
+                        prev.span = (coord - end.last_coord) as usize;
+                    }
+                    None => {
+                        end.first = Some(new);
+                        end.first_coord = coord;
+                    }
+                }
+                end.last = Some(new);
+                end.last_coord = coord;
+            }
+
+            self.len += 1;
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        unsafe {
+            let node = self.ends[0].first?;
+            let boxed_node = Box::from_raw(node.as_ptr());
+
+            for (level, link) in boxed_node.levels.iter().enumerate() {
+                let end = &mut self.ends[level];
+                end.first = link.next;
+                match link.next {
+                    Some(next) => {
+                        (*next.as_ptr()).levels[level].prev = None;
+                        end.first_coord = self.base + link.span as isize;
+                    }
+                    None => end.last = None,
+                }
+            }
+
+            self.base += 1;
+            self.len -= 1;
+            self.shrink();
+            Some(boxed_node.elem)
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        unsafe {
+            let node = self.ends[0].last?;
+            let boxed_node = Box::from_raw(node.as_ptr());
+
+            for (level, link) in boxed_node.levels.iter().enumerate() {
+                let end = &mut self.ends[level];
+                end.last = link.prev;
+                match link.prev {
+                    Some(prev) => {
+                        let prev = &mut (*prev.as_ptr()).levels[level];
+                        prev.next = None;
+                        end.last_coord -= prev.span as isize;
+                        prev.span = 0;
+                    }
+                    None => end.first = None,
+                }
+            }
+
+            self.len -= 1;
+            self.shrink();
+            Some(boxed_node.elem)
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let node = self.node_at(index)?;
+        unsafe { Some(&(*node.as_ptr()).elem) }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let node = self.node_at(index)?;
+        unsafe { Some(&mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn insert(&mut self, index: usize, elem: T) {
+        assert!(
+            index <= self.len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            self.len
+        );
+
+        if index == 0 {
+            return self.push_front(elem);
+        }
+        if index == self.len {
+            return self.push_back(elem);
+        }
+
+        unsafe {
+            let preds = self.preds(index);
+            let new = self.new_node(elem);
+            let levels = &(*new.as_ptr()).levels;
+            let new_height = levels.len();
+            let coord = self.base + index as isize;
+
+            for (level, &(pred, after)) in preds.iter().enumerate().take(self.height) {
+                let next = self.next(pred, level);
+                let next_pos = next.map_or(0, |_| after + self.span(pred, level));
+                let end = &mut self.ends[level];
+
+                if level < new_height {
+                    let link = &mut (*new.as_ptr()).levels[level];
+                    link.prev = pred;
+                    link.next = next;
+                    match next {
+                        Some(next) => {
+                            link.span = next_pos - index;
+                            (*next.as_ptr()).levels[level].prev = Some(new);
+                        }
+                        None => {
+                            end.last = Some(new);
+                            end.last_coord = coord;
+                        }
+                    }
+                    match pred {
+                        Some(pred) => {
+                            let pred = &mut (*pred.as_ptr()).levels[level];
+                            pred.next = Some(new);
+                            pred.span = index + 1 - after;
+                        }
+                        None => {
+                            end.first = Some(new);
+                            end.first_coord = coord;
+                        }
+                    }
+                } else if next.is_some() {
+                    match pred {
+                        Some(pred) => (*pred.as_ptr()).levels[level].span += 1,
+                        None => end.first_coord += 1,
+                    }
+                }
+
+                if next.is_some() {
+                    end.last_coord += 1;
+                }
+            }
+
+            self.len += 1;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+        if index == 0 {
+            return self.pop_front();
+        }
+        if index == self.len - 1 {
+            return self.pop_back();
+        }
+
+        unsafe {
+            let preds = self.preds(index);
+            let node = self.next(preds[0].0, 0).unwrap();
+            let boxed_node = Box::from_raw(node.as_ptr());
+
+            for (level, &(pred, after)) in preds.iter().enumerate().take(self.height) {
+                let next = match boxed_node.levels.get(level) {
+                    Some(link) => {
+                        let end = &mut self.ends[level];
+                        match link.next {
+                            Some(next) => (*next.as_ptr()).levels[level].prev = pred,
+                            None => {
+                                end.last = pred;
+                                end.last_coord = self.base + after as isize - 1;
+                            }
+                        }
+                        match pred {
+                            Some(pred) => {
+                                let pred = &mut (*pred.as_ptr()).levels[level];
+                                pred.next = link.next;
+                                pred.span = match link.next {
+                                    Some(_) => pred.span + link.span - 1,
+                                    None => 0,
+                                };
+                            }
+                            None => {
+                                end.first = link.next;
+                                end.first_coord = self.base + (index + link.span) as isize - 1;
+                            }
+                        }
+                        link.next
+                    }
+                    None => {
+                        let next = self.next(pred, level);
+                        if next.is_some() {
+                            match pred {
+                                Some(pred) => (*pred.as_ptr()).levels[level].span -= 1,
+                                None => self.ends[level].first_coord -= 1,
+                            }
+                        }
+                        next
+                    }
+                };
+
+                if next.is_some() {
+                    self.ends[level].last_coord -= 1;
+                }
+            }
+
+            self.len -= 1;
+            self.shrink();
+            Some(boxed_node.elem)
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            return mem::swap(self, other);
+        }
+
+        // Positions in `other` move up by `self.len`.
+        let shift = self.base + self.len as isize - other.base;
+        unsafe {
+            for (level, (end, theirs)) in self.ends.iter_mut().zip(&other.ends).enumerate() {
+                let Some(first) = theirs.first else {
+                    break;
+                };
+
+                let first_coord = theirs.first_coord + shift;
+                match end.last {
+                    Some(last) => {
+                        let link = &mut (*last.as_ptr()).levels[level];
+                        link.next = Some(first);
+                        link.span = (first_coord - end.last_coord) as usize;
+                        (*first.as_ptr()).levels[level].prev = Some(last);
+                    }
+                    None => {
+                        end.first = Some(first);
+                        end.first_coord = first_coord;
+                    }
+                }
+                end.last = theirs.last;
+                end.last_coord = theirs.last_coord + shift;
+            }
+        }
+
+        self.height = self.height.max(other.height);
+        self.len += other.len;
+        other.forget();
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(
+            at <= self.len,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            self.len
+        );
+
+        if at == 0 {
+            return mem::take(self);
+        }
+
+        let mut other = Self::new();
+        if at == self.len {
+            return other;
+        }
+
+        unsafe {
+            let preds = self.preds(at);
+            other.base = self.base + at as isize;
+            other.len = self.len - at;
+            other.height = self.height;
+
+            for (level, &(pred, after)) in preds.iter().enumerate().take(self.height) {
+                let Some(next) = self.next(pred, level) else {
+                    continue;
+                };
+                let next_pos = after + self.span(pred, level);
+
+                let end = &mut self.ends[level];
+                other.ends[level] = End {
+                    first: Some(next),
+                    last: end.last,
+                    first_coord: self.base + next_pos as isize - 1,
+                    last_coord: end.last_coord,
+                };
+                (*next.as_ptr()).levels[level].prev = None;
+
+                end.last = pred;
+                match pred {
+                    Some(pred) => {
+                        let pred = &mut (*pred.as_ptr()).levels[level];
+                        pred.next = None;
+                        pred.span = 0;
+                        end.last_coord = self.base + after as isize - 1;
+                    }
+                    None => end.first = None,
+                }
+            }
+
+            self.len = at;
+            self.shrink();
+            other.shrink();
+        }
+        other
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.ends[0].first,
+            back: self.ends[0].last,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.ends[0].first,
+            back: self.ends[0].last,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            list: self,
+            cursor: None,
+            index: None,
+        }
+    }
+
+    fn new_node(&mut self, elem: T) -> NonNull<Node<T>> {
+        // xorshift64, a level up with probability 1/4.
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let height = (self.seed.trailing_zeros() as usize / 2 + 1).min(MAX_LEVEL);
+        self.height = self.height.max(height);
+
+        let levels = (0..height)
+            .map(|_| Level {
+                prev: None,
+                next: None,
+                span: 0,
+            })
+            .collect();
+        unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Node { levels, elem }))) }
+    }
+
+    fn shrink(&mut self) {
+        while self.height > 0 && self.ends[self.height - 1].first.is_none() {
+            self.height -= 1;
+        }
+    }
+
+    // Gives up the nodes without freeing them, after they've been moved into
+    // another list.
+    fn forget(&mut self) {
+        self.ends = core::array::from_fn(|_| End::empty());
+        self.height = 0;
+        self.len = 0;
+    }
+
+    // `None` for `pred` stands for the head.
+    unsafe fn next(&self, pred: Link<T>, level: usize) -> Link<T> {
+        match pred {
+            Some(pred) => (*pred.as_ptr()).levels[level].next,
+            None => self.ends[level].first,
+        }
+    }
+
+    // Positions are counted from 1 here so the head can sit at 0.
+    unsafe fn span(&self, pred: Link<T>, level: usize) -> usize {
+        match pred {
+            Some(pred) => (*pred.as_ptr()).levels[level].span,
+            None => (self.ends[level].first_coord - self.base + 1) as usize,
+        }
+    }
+
+    // For every level, the last node before `index` and how many elements
+    // come up to and including it.
+    fn preds(&self, index: usize) -> [(Link<T>, usize); MAX_LEVEL] {
+        let mut preds = [(None, 0); MAX_LEVEL];
+        let (mut pred, mut after) = (None, 0);
+
+        unsafe {
+            for level in (0..self.height).rev() {
+                while let Some(next) = self.next(pred, level) {
+                    let next_after = after + self.span(pred, level);
+                    if next_after > index {
+                        break;
+                    }
+                    pred = Some(next);
+                    after = next_after;
+                }
+                preds[level] = (pred, after);
+            }
+        }
+        preds
+    }
+
+    fn node_at(&self, index: usize) -> Link<T> {
+        if index >= self.len {
+            None
+        } else if index == self.len - 1 {
+            self.ends[0].last
+        } else {
+            unsafe { self.next(self.preds(index)[0].0, 0) }
+        }
+    }
+}
+
+impl<T> End<T> {
+    fn empty() -> Self {
+        Self {
+            first: None,
+            last: None,
+            first_coord: 0,
+            last_coord: 0,
+        }
+    }
+}
+
+impl<T> Drop for IndexedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for IndexedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for IndexedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for IndexedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for IndexedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for IndexedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for IndexedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for IndexedList<T> {}
+
+impl<T: Hash> Hash for IndexedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a IndexedList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut IndexedList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for IndexedList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.front.map(|node| unsafe {
+                self.len -= 1;
+                self.front = (*node.as_ptr()).levels[0].next;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.back.map(|node| unsafe {
+                self.len -= 1;
+                self.back = (*node.as_ptr()).levels[0].prev;
+                &(*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.front.map(|node| unsafe {
+                self.len -= 1;
+                self.front = (*node.as_ptr()).levels[0].next;
+                &mut (*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len > 0 {
+            self.back.map(|node| unsafe {
+                self.len -= 1;
+                self.back = (*node.as_ptr()).levels[0].prev;
+                &mut (*node.as_ptr()).elem
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn as_list(&self) -> &IndexedList<T> {
+        self.list
+    }
+
+    pub fn move_next(&mut self) {
+        if let Some(cursor) = self.cursor {
+            unsafe {
+                self.cursor = (*cursor.as_ptr()).levels[0].next;
+                if self.cursor.is_some() {
+                    *self.index.as_mut().unwrap() += 1;
+                } else {
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            self.cursor = self.list.ends[0].first;
+            self.index = Some(0);
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if let Some(cursor) = self.cursor {
+            unsafe {
+                self.cursor = (*cursor.as_ptr()).levels[0].prev;
+                if self.cursor.is_some() {
+                    *self.index.as_mut().unwrap() -= 1;
+                } else {
+                    self.index = None;
+                }
+            }
+        } else if !self.list.is_empty() {
+            self.cursor = self.list.ends[0].last;
+            self.index = Some(self.list.len - 1);
+        }
+    }
+
+    // Jumps straight to `index`, or the ghost if it's out of bounds.
+    pub fn seek(&mut self, index: usize) {
+        self.cursor = self.list.node_at(index);
+        self.index = self.cursor.map(|_| index);
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.cursor.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.cursor {
+                Some(cursor) => (*cursor.as_ptr()).levels[0].next,
+                None => self.list.ends[0].first,
+            };
+            next.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        unsafe {
+            let prev = match self.cursor {
+                Some(cursor) => (*cursor.as_ptr()).levels[0].prev,
+                None => self.list.ends[0].last,
+            };
+            prev.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    pub fn insert_before(&mut self, elem: T) {
+        match self.index {
+            Some(index) => {
+                self.list.insert(index, elem);
+                self.index = Some(index + 1);
+            }
+            None => self.list.push_back(elem),
+        }
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        match self.index {
+            Some(index) => self.list.insert(index + 1, elem),
+            None => self.list.push_front(elem),
+        }
+    }
+
+    // Moves on to the next element, or the ghost if there isn't one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let cursor = self.cursor?;
+        let index = self.index?;
+
+        unsafe {
+            self.cursor = (*cursor.as_ptr()).levels[0].next;
+        }
+        if self.cursor.is_none() {
+            self.index = None;
+        }
+        self.list.remove(index)
+    }
+}
+
+unsafe impl<T: Send> Send for IndexedList<T> {}
+unsafe impl<T: Sync> Sync for IndexedList<T> {}
+
+unsafe impl<'a, T: Send> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
@@ -0,0 +1,310 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::linked_list::{CursorMut, LinkedList};
+
+pub struct Journal<T> {
+    list: LinkedList<T>,
+    log: Log<T>,
+}
+
+pub struct JournalCursor<'a, T> {
+    cursor: CursorMut<'a, T>,
+    log: &'a mut Log<T>,
+}
+
+struct Log<T> {
+    undo: Vec<Edit<T>>,
+    redo: Vec<Edit<T>>,
+    checkpoints: Vec<(String, usize)>,
+}
+
+// Every supported operation boils down to "at this index, these nodes were
+// taken out and this many were put in". Reverting an edit swaps the two, which
+// makes the reverted edit exactly the one that redoes it.
+struct Edit<T> {
+    at: usize,
+    removed: LinkedList<T>,
+    inserted: usize,
+}
+
+impl<T> Log<T> {
+    // Hands back what the edit removed, now that the log owns it.
+    fn record(&mut self, edit: Edit<T>) -> &LinkedList<T> {
+        let depth = self.undo.len();
+        self.checkpoints.retain(|&(_, at)| at <= depth);
+        self.redo.clear();
+        self.undo.push(edit);
+        &self.undo.last().unwrap().removed
+    }
+}
+
+impl<T> Edit<T> {
+    fn inserted(at: usize, inserted: usize) -> Self {
+        Self {
+            at,
+            removed: LinkedList::new(),
+            inserted,
+        }
+    }
+
+    fn removed(at: usize, removed: LinkedList<T>) -> Self {
+        Self {
+            at,
+            removed,
+            inserted: 0,
+        }
+    }
+
+    fn removed_one(at: usize, elem: T) -> Self {
+        let mut removed = LinkedList::new();
+        removed.push_back(elem);
+        Self::removed(at, removed)
+    }
+
+    // One walk from the nearer end to just past the inserted run, then
+    // O(inserted) to cut it out. Edits at either end cost O(inserted).
+    fn revert(mut self, list: &mut LinkedList<T>) -> Self {
+        let end = self.at + self.inserted;
+        let len = list.len();
+        let mut cursor = list.cursor_mut();
+        if end < len / 2 {
+            for _ in 0..=end {
+                cursor.move_next();
+            }
+        } else {
+            for _ in end..len {
+                cursor.move_prev();
+            }
+        }
+
+        let mut head = cursor.split_before();
+        let taken = head.split_off(self.at);
+        self.inserted = self.removed.len();
+        head.append(&mut self.removed);
+        cursor.splice_before(head);
+        self.removed = taken;
+        self
+    }
+}
+
+impl<T> Journal<T> {
+    pub fn new() -> Self {
+        Self::from(LinkedList::new())
+    }
+
+    pub fn list(&self) -> &LinkedList<T> {
+        &self.list
+    }
+
+    pub fn into_inner(self) -> LinkedList<T> {
+        self.list
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        self.list.push_front(elem);
+        self.log.record(Edit::inserted(0, 1));
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        self.list.push_back(elem);
+        self.log.record(Edit::inserted(self.list.len() - 1, 1));
+    }
+
+    // Removing operations move what they took into the log, since undoing
+    // needs it back, and lend it to the caller from there. Nothing is cloned.
+    pub fn pop_front(&mut self) -> Option<&T> {
+        let elem = self.list.pop_front()?;
+        self.log.record(Edit::removed_one(0, elem)).front()
+    }
+
+    pub fn pop_back(&mut self) -> Option<&T> {
+        let elem = self.list.pop_back()?;
+        let at = self.list.len();
+        self.log.record(Edit::removed_one(at, elem)).front()
+    }
+
+    pub fn cursor_mut(&mut self) -> JournalCursor<'_, T> {
+        JournalCursor {
+            cursor: self.list.cursor_mut(),
+            log: &mut self.log,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.log.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.log.redo.is_empty()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.log.undo.pop() {
+            Some(edit) => {
+                self.log.redo.push(edit.revert(&mut self.list));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.log.redo.pop() {
+            Some(edit) => {
+                self.log.undo.push(edit.revert(&mut self.list));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn checkpoint(&mut self, name: impl Into<String>) {
+        let name = name.into();
+        let depth = self.log.undo.len();
+        self.log.checkpoints.retain(|(other, _)| *other != name);
+        self.log.checkpoints.push((name, depth));
+    }
+
+    pub fn restore(&mut self, name: &str) -> bool {
+        let depth = match self.log.checkpoints.iter().find(|(other, _)| other == name) {
+            Some(&(_, depth)) => depth,
+            None => return false,
+        };
+
+        while self.log.undo.len() > depth {
+            self.undo();
+        }
+        while self.log.undo.len() < depth {
+            self.redo();
+        }
+        true
+    }
+
+    pub fn clear_history(&mut self) {
+        self.log.undo.clear();
+        self.log.redo.clear();
+        self.log.checkpoints.clear();
+    }
+}
+
+impl<T> Default for Journal<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<LinkedList<T>> for Journal<T> {
+    fn from(list: LinkedList<T>) -> Self {
+        Self {
+            list,
+            log: Log {
+                undo: Vec::new(),
+                redo: Vec::new(),
+                checkpoints: Vec::new(),
+            },
+        }
+    }
+}
+
+impl<'a, T> JournalCursor<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cursor.index()
+    }
+
+    pub fn move_next(&mut self) {
+        self.cursor.move_next()
+    }
+
+    pub fn move_prev(&mut self) {
+        self.cursor.move_prev()
+    }
+
+    // Elements are handed out shared: writing through them would change the
+    // list behind the journal's back.
+    pub fn current(&mut self) -> Option<&T> {
+        self.cursor.current().map(|elem| &*elem)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&T> {
+        self.cursor.peek_next().map(|elem| &*elem)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&T> {
+        self.cursor.peek_prev().map(|elem| &*elem)
+    }
+
+    fn before(&self) -> usize {
+        self.index().unwrap_or(self.cursor.as_list().len())
+    }
+
+    fn after(&self) -> usize {
+        self.index().map_or(0, |index| index + 1)
+    }
+
+    pub fn insert_before(&mut self, elem: T) {
+        let at = self.before();
+        self.cursor.insert_before(elem);
+        self.log.record(Edit::inserted(at, 1));
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        let at = self.after();
+        self.cursor.insert_after(elem);
+        self.log.record(Edit::inserted(at, 1));
+    }
+
+    pub fn remove_current(&mut self) -> Option<&T> {
+        let at = self.index()?;
+        let elem = self.cursor.remove_current()?;
+        self.log.record(Edit::removed_one(at, elem)).front()
+    }
+
+    // `None` if there was nothing to split off, which isn't logged either.
+    pub fn split_before(&mut self) -> Option<&LinkedList<T>> {
+        let removed = self.cursor.split_before();
+        if removed.is_empty() {
+            return None;
+        }
+        Some(self.log.record(Edit::removed(0, removed)))
+    }
+
+    pub fn split_after(&mut self) -> Option<&LinkedList<T>> {
+        let at = self.after();
+        let removed = self.cursor.split_after();
+        if removed.is_empty() {
+            return None;
+        }
+        Some(self.log.record(Edit::removed(at, removed)))
+    }
+
+    pub fn splice_before(&mut self, input: LinkedList<T>) {
+        if input.is_empty() {
+            return;
+        }
+
+        let at = self.before();
+        let inserted = input.len();
+        self.cursor.splice_before(input);
+        self.log.record(Edit::inserted(at, inserted));
+    }
+
+    pub fn splice_after(&mut self, input: LinkedList<T>) {
+        if input.is_empty() {
+            return;
+        }
+
+        let at = self.after();
+        let inserted = input.len();
+        self.cursor.splice_after(input);
+        self.log.record(Edit::inserted(at, inserted));
+    }
+}
@@ -0,0 +1,39 @@
+// Without the `std` feature this builds on `core` and `alloc` alone. What
+// needs threads, clocks or hashing only comes with `std`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod array_list;
+pub mod bounded;
+#[cfg(feature = "std")]
+pub mod cache;
+#[cfg(feature = "std")]
+pub mod channel;
+pub mod cow;
+pub mod deque;
+#[cfg(feature = "std")]
+pub mod expiring;
+pub mod indexed;
+pub mod journal;
+#[cfg(feature = "std")]
+pub mod linked_hash;
+pub mod linked_list;
+#[cfg(feature = "std")]
+pub mod lru;
+pub mod persistent;
+pub mod singly;
+pub mod sorted;
+#[cfg(test)]
+mod test;
+pub mod text;
+#[cfg(feature = "std")]
+pub mod timer_wheel;
+pub mod tree_list;
+pub mod weighted;
+pub mod window;
+#[cfg(feature = "std")]
+pub mod work_stealing;
+pub mod xor;
+
+pub use linked_list::{CursorMut, IntoIter, Iter, IterMut, LinkedList};
@@ -0,0 +1,746 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+use std::iter::FromIterator;
+
+use crate::linked_list::{self, LinkedList, NodeRef};
+
+pub struct LinkedHashMap<K, V> {
+    list: LinkedList<(K, V)>,
+    map: HashMap<K, NodeRef<(K, V)>>,
+}
+
+pub struct LinkedHashSet<T> {
+    map: LinkedHashMap<T, ()>,
+}
+
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    map: &'a mut LinkedHashMap<K, V>,
+    node: NodeRef<(K, V)>,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    map: &'a mut LinkedHashMap<K, V>,
+    key: K,
+}
+
+pub struct CursorMut<'a, K, V> {
+    map: &'a mut LinkedHashMap<K, V>,
+    cursor: Option<NodeRef<(K, V)>>,
+}
+
+pub struct Iter<'a, K, V> {
+    iter: linked_list::Iter<'a, (K, V)>,
+}
+
+pub struct IterMut<'a, K, V> {
+    iter: linked_list::IterMut<'a, (K, V)>,
+}
+
+pub struct IntoIter<K, V> {
+    iter: linked_list::IntoIter<(K, V)>,
+}
+
+pub struct Keys<'a, K, V> {
+    iter: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    iter: Iter<'a, K, V>,
+}
+
+pub struct SetIter<'a, T> {
+    iter: Keys<'a, T, ()>,
+}
+
+pub struct SetIntoIter<T> {
+    iter: IntoIter<T, ()>,
+}
+
+impl<K, V> LinkedHashMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.list.front().map(|(key, value)| (key, value))
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(key, value)| (key, value))
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.list.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            iter: self.list.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { iter: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { iter: self.iter() }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, K, V> {
+        CursorMut {
+            map: self,
+            cursor: None,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LinkedHashMap<K, V> {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.map.get(&key) {
+            Some(&node) => Entry::Occupied(OccupiedEntry { map: self, node }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(key)?;
+        unsafe { Some(&self.list.node(node).1) }
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(key)?;
+        unsafe { Some(&mut self.list.node_mut(node).1) }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.remove(key)?;
+        unsafe { Some(self.list.remove_node(node)) }
+    }
+
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.list.pop_front()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.list.pop_back()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    pub fn move_to_front<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(&node) => {
+                unsafe { self.list.move_to_front(node) };
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn move_to_back<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(&node) => {
+                unsafe { self.list.move_to_back(node) };
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V> Entry<'a, K, V> {
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        unsafe { &self.map.list.node(self.node).0 }
+    }
+
+    pub fn get(&self) -> &V {
+        unsafe { &self.map.list.node(self.node).1 }
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        unsafe { &mut self.map.list.node_mut(self.node).1 }
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        unsafe { &mut self.map.list.node_mut(self.node).1 }
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V> OccupiedEntry<'a, K, V> {
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        let entry = unsafe { self.map.list.remove_node(self.node) };
+        self.map.map.remove(&entry.0);
+        entry
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V> VacantEntry<'a, K, V> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        let node = self.map.list.push_back_node((self.key.clone(), value));
+        self.map.map.insert(self.key, node);
+        unsafe { &mut self.map.list.node_mut(node).1 }
+    }
+}
+
+impl<'a, K, V> CursorMut<'a, K, V> {
+    pub fn move_next(&mut self) {
+        self.cursor = match self.cursor {
+            Some(node) => unsafe { self.map.list.next_node(node) },
+            None => self.map.list.front_node(),
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        self.cursor = match self.cursor {
+            Some(node) => unsafe { self.map.list.prev_node(node) },
+            None => self.map.list.back_node(),
+        };
+    }
+
+    pub fn current(&mut self) -> Option<(&K, &mut V)> {
+        let node = self.cursor?;
+        let (key, value) = unsafe { self.map.list.node_mut(node) };
+        Some((&*key, value))
+    }
+
+    pub fn peek_next(&mut self) -> Option<(&K, &mut V)> {
+        let next = match self.cursor {
+            Some(node) => unsafe { self.map.list.next_node(node) },
+            None => self.map.list.front_node(),
+        }?;
+        let (key, value) = unsafe { self.map.list.node_mut(next) };
+        Some((&*key, value))
+    }
+
+    pub fn peek_prev(&mut self) -> Option<(&K, &mut V)> {
+        let prev = match self.cursor {
+            Some(node) => unsafe { self.map.list.prev_node(node) },
+            None => self.map.list.back_node(),
+        }?;
+        let (key, value) = unsafe { self.map.list.node_mut(prev) };
+        Some((&*key, value))
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V> CursorMut<'a, K, V> {
+    pub fn remove_current(&mut self) -> Option<(K, V)> {
+        let node = self.cursor?;
+        unsafe {
+            self.cursor = self.map.list.next_node(node);
+            let entry = self.map.list.remove_node(node);
+            self.map.map.remove(&entry.0);
+            Some(entry)
+        }
+    }
+}
+
+impl<T> LinkedHashSet<T> {
+    pub fn new() -> Self {
+        Self {
+            map: LinkedHashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.map.front().map(|(elem, _)| elem)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.map.back().map(|(elem, _)| elem)
+    }
+
+    pub fn iter(&self) -> SetIter<'_, T> {
+        SetIter {
+            iter: self.map.keys(),
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> LinkedHashSet<T> {
+    pub fn insert(&mut self, elem: T) -> bool {
+        match self.map.entry(elem) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(elem)
+    }
+
+    pub fn remove<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(elem).is_some()
+    }
+
+    pub fn take<Q>(&mut self, elem: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(elem).map(|(elem, _)| elem)
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.map.pop_front().map(|(elem, _)| elem)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.map.pop_back().map(|(elem, _)| elem)
+    }
+
+    pub fn move_to_front<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.move_to_front(elem)
+    }
+
+    pub fn move_to_back<Q>(&mut self, elem: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.move_to_back(elem)
+    }
+}
+
+impl<K, V> Default for LinkedHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Default for LinkedHashSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Clone for LinkedHashMap<K, V> {
+    fn clone(&self) -> Self {
+        self.iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Clone for LinkedHashSet<T> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Extend<(K, V)> for LinkedHashMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> FromIterator<(K, V)> for LinkedHashMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<T: Hash + Eq + Clone> Extend<T> for LinkedHashSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.insert(elem);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> FromIterator<T> for LinkedHashSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LinkedHashMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Debug> Debug for LinkedHashSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a LinkedHashMap<K, V> {
+    type IntoIter = Iter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut LinkedHashMap<K, V> {
+    type IntoIter = IterMut<'a, K, V>;
+    type Item = (&'a K, &'a mut V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V> IntoIterator for LinkedHashMap<K, V> {
+    type IntoIter = IntoIter<K, V>;
+    type Item = (K, V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.list.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedHashSet<T> {
+    type IntoIter = SetIter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for LinkedHashSet<T> {
+    type IntoIter = SetIntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SetIntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (&*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(key, value)| (&*key, value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<'a, T> Iterator for SetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for SetIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SetIter<'a, T> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<T> Iterator for SetIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(elem, _)| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for SetIntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(elem, _)| elem)
+    }
+}
+
+impl<T> ExactSizeIterator for SetIntoIter<T> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
@@ -1,9 +1,11 @@
-use std::cmp::Ordering;
-use std::fmt::{self, Debug};
-use std::hash::{Hash, Hasher};
-use std::iter::FromIterator;
-use std::marker::PhantomData;
-use std::ptr::NonNull;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
 
 pub struct LinkedList<T> {
     front: Link<T>,
@@ -44,6 +46,14 @@ pub struct CursorMut<'a, T> {
     index: Option<usize>,
 }
 
+// A stable handle to one node, for structures that keep their own index into a
+// list (maps, caches, timers) and need O(1) access without walking it. The
+// handle stays valid while the node is linked into some list, including after
+// it's been moved from one list to another with `unlink_node`/`link_*`.
+// Only the `std` structures use these.
+#[cfg(feature = "std")]
+pub(crate) struct NodeRef<T>(NonNull<Node<T>>);
+
 impl<T> LinkedList<T> {
     pub fn new() -> Self {
         Self {
@@ -144,7 +154,7 @@ impl<T> LinkedList<T> {
         unsafe { self.back.map(|node| &(*node.as_ptr()).elem) }
     }
 
-    pub fn back_mut(&self) -> Option<&mut T> {
+    pub fn back_mut(&mut self) -> Option<&mut T> {
         unsafe { self.back.map(|node| &mut (*node.as_ptr()).elem) }
     }
 
@@ -160,7 +170,7 @@ impl<T> LinkedList<T> {
         while self.pop_front().is_some() {}
     }
 
-    pub fn iter(&self) -> Iter<T> {
+    pub fn iter(&self) -> Iter<'_, T> {
         Iter {
             front: self.front,
             back: self.back,
@@ -169,7 +179,7 @@ impl<T> LinkedList<T> {
         }
     }
 
-    pub fn iter_mut(&mut self) -> IterMut<T> {
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
             front: self.front,
             back: self.back,
@@ -178,13 +188,200 @@ impl<T> LinkedList<T> {
         }
     }
 
-    pub fn cursor_mut(&mut self) -> CursorMut<T> {
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
         CursorMut {
             list: self,
             cursor: None,
             index: None,
         }
     }
+
+    pub fn append(&mut self, other: &mut Self) {
+        let mut cursor = self.cursor_mut();
+        cursor.splice_before(core::mem::take(other));
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(
+            at <= self.len,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            self.len
+        );
+
+        if at == 0 {
+            return core::mem::take(self);
+        }
+
+        let len = self.len;
+        let mut cursor = self.cursor_mut();
+        if at - 1 < len / 2 {
+            for _ in 0..at {
+                cursor.move_next();
+            }
+        } else {
+            for _ in at - 1..len {
+                cursor.move_prev();
+            }
+        }
+        cursor.split_after()
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    // Stable. Sorts pointers to the nodes and relinks them in that order, so no
+    // element moves. The relinking happens when `Relink` drops, which keeps the
+    // list whole even if `compare` panics halfway through.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        struct Relink<'a, T> {
+            list: &'a mut LinkedList<T>,
+            nodes: Vec<NonNull<Node<T>>>,
+        }
+
+        impl<T> Drop for Relink<'_, T> {
+            fn drop(&mut self) {
+                let mut prev: Link<T> = None;
+                for &node in &self.nodes {
+                    unsafe {
+                        (*node.as_ptr()).front = prev;
+                        match prev {
+                            Some(prev) => (*prev.as_ptr()).back = Some(node),
+                            None => self.list.front = Some(node),
+                        }
+                    }
+                    prev = Some(node);
+                }
+                if let Some(back) = prev {
+                    unsafe { (*back.as_ptr()).back = None };
+                    self.list.back = Some(back);
+                }
+            }
+        }
+
+        if self.len < 2 {
+            return;
+        }
+
+        let mut nodes = Vec::with_capacity(self.len);
+        let mut link = self.front;
+        while let Some(node) = link {
+            nodes.push(node);
+            link = unsafe { (*node.as_ptr()).back };
+        }
+
+        let mut relink = Relink { list: self, nodes };
+        relink
+            .nodes
+            .sort_by(|a, b| unsafe { compare(&(*a.as_ptr()).elem, &(*b.as_ptr()).elem) });
+    }
+}
+
+// Safety: every `unsafe fn` here takes a `NodeRef` that must currently be
+// linked into `self`, except `link_front`/`link_back`, which take one that has
+// just been unlinked from some list and not linked anywhere since.
+#[cfg(feature = "std")]
+impl<T> LinkedList<T> {
+    pub(crate) fn push_front_node(&mut self, elem: T) -> NodeRef<T> {
+        self.push_front(elem);
+        NodeRef(self.front.unwrap())
+    }
+
+    pub(crate) fn push_back_node(&mut self, elem: T) -> NodeRef<T> {
+        self.push_back(elem);
+        NodeRef(self.back.unwrap())
+    }
+
+    pub(crate) fn front_node(&self) -> Option<NodeRef<T>> {
+        self.front.map(NodeRef)
+    }
+
+    pub(crate) fn back_node(&self) -> Option<NodeRef<T>> {
+        self.back.map(NodeRef)
+    }
+
+    pub(crate) unsafe fn node(&self, node: NodeRef<T>) -> &T {
+        &(*node.0.as_ptr()).elem
+    }
+
+    pub(crate) unsafe fn node_mut(&mut self, node: NodeRef<T>) -> &mut T {
+        &mut (*node.0.as_ptr()).elem
+    }
+
+    pub(crate) unsafe fn next_node(&self, node: NodeRef<T>) -> Option<NodeRef<T>> {
+        (*node.0.as_ptr()).back.map(NodeRef)
+    }
+
+    pub(crate) unsafe fn prev_node(&self, node: NodeRef<T>) -> Option<NodeRef<T>> {
+        (*node.0.as_ptr()).front.map(NodeRef)
+    }
+
+    pub(crate) unsafe fn unlink_node(&mut self, node: NodeRef<T>) {
+        let node = node.0.as_ptr();
+        let prev = (*node).front.take();
+        let next = (*node).back.take();
+
+        if let Some(prev) = prev {
+            (*prev.as_ptr()).back = next;
+        } else {
+            self.front = next;
+        }
+
+        if let Some(next) = next {
+            (*next.as_ptr()).front = prev;
+        } else {
+            self.back = prev;
+        }
+
+        self.len -= 1;
+    }
+
+    pub(crate) unsafe fn link_front(&mut self, node: NodeRef<T>) {
+        if let Some(old) = self.front {
+            (*old.as_ptr()).front = Some(node.0);
+            (*node.0.as_ptr()).back = Some(old);
+        } else {
+            self.back = Some(node.0);
+        }
+
+        self.front = Some(node.0);
+        self.len += 1;
+    }
+
+    pub(crate) unsafe fn link_back(&mut self, node: NodeRef<T>) {
+        if let Some(old) = self.back {
+            (*old.as_ptr()).back = Some(node.0);
+            (*node.0.as_ptr()).front = Some(old);
+        } else {
+            self.front = Some(node.0);
+        }
+
+        self.back = Some(node.0);
+        self.len += 1;
+    }
+
+    pub(crate) unsafe fn remove_node(&mut self, node: NodeRef<T>) -> T {
+        self.unlink_node(node);
+        Box::from_raw(node.0.as_ptr()).elem
+    }
+
+    pub(crate) unsafe fn move_to_front(&mut self, node: NodeRef<T>) {
+        if self.front != Some(node.0) {
+            self.unlink_node(node);
+            self.link_front(node);
+        }
+    }
+
+    pub(crate) unsafe fn move_to_back(&mut self, node: NodeRef<T>) {
+        if self.back != Some(node.0) {
+            self.unlink_node(node);
+            self.link_back(node);
+        }
+    }
 }
 
 impl<T> Drop for LinkedList<T> {
@@ -397,6 +594,10 @@ impl<'a, T> CursorMut<'a, T> {
         self.index
     }
 
+    pub fn as_list(&self) -> &LinkedList<T> {
+        self.list
+    }
+
     pub fn move_next(&mut self) {
         if let Some(cursor) = self.cursor {
             unsafe {
@@ -410,7 +611,6 @@ impl<'a, T> CursorMut<'a, T> {
         } else if !self.list.is_empty() {
             self.cursor = self.list.front;
             self.index = Some(0);
-        } else {
         }
     }
 
@@ -427,7 +627,6 @@ impl<'a, T> CursorMut<'a, T> {
         } else if !self.list.is_empty() {
             self.cursor = self.list.back;
             self.index = Some(self.list.len - 1);
-        } else {
         }
     }
 
@@ -472,7 +671,8 @@ impl<'a, T> CursorMut<'a, T> {
                 let new_index = Some(0);
 
                 let output_len = old_len - new_len;
-                let output_front = self.list.front;
+                // Nothing before the cursor, so nothing to hand out.
+                let output_front = prev.and(self.list.front);
                 let output_back = prev;
 
                 if let Some(prev) = prev {
@@ -493,7 +693,7 @@ impl<'a, T> CursorMut<'a, T> {
                 }
             }
         } else {
-            std::mem::replace(self.list, LinkedList::new())
+            core::mem::take(self.list)
         }
     }
 
@@ -510,7 +710,7 @@ impl<'a, T> CursorMut<'a, T> {
                 let new_index = Some(old_index);
 
                 let output_len = old_len - new_len;
-                let output_back = self.list.back;
+                let output_back = next.and(self.list.back);
                 let output_front = next;
 
                 if let Some(next) = next {
@@ -531,7 +731,7 @@ impl<'a, T> CursorMut<'a, T> {
                 }
             }
         } else {
-            std::mem::replace(self.list, LinkedList::new())
+            core::mem::take(self.list)
         }
     }
 
@@ -562,7 +762,7 @@ impl<'a, T> CursorMut<'a, T> {
                 (*in_front.as_ptr()).front = Some(back);
                 self.list.back = Some(in_back);
             } else {
-                std::mem::swap(self.list, &mut input);
+                core::mem::swap(self.list, &mut input);
             }
 
             self.list.len += input.len;
@@ -595,13 +795,79 @@ impl<'a, T> CursorMut<'a, T> {
                 (*in_back.as_ptr()).back = Some(front);
                 self.list.front = Some(in_front);
             } else {
-                std::mem::swap(self.list, &mut input);
+                core::mem::swap(self.list, &mut input);
             }
 
             self.list.len += input.len;
             input.len = 0;
         }
     }
+
+    pub fn insert_before(&mut self, elem: T) {
+        let mut input = LinkedList::new();
+        input.push_back(elem);
+        self.splice_before(input);
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        let mut input = LinkedList::new();
+        input.push_back(elem);
+        self.splice_after(input);
+    }
+
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.cursor?;
+
+        unsafe {
+            let boxed_node = Box::from_raw(node.as_ptr());
+            let prev = boxed_node.front;
+            let next = boxed_node.back;
+
+            if let Some(prev) = prev {
+                (*prev.as_ptr()).back = next;
+            } else {
+                self.list.front = next;
+            }
+
+            if let Some(next) = next {
+                (*next.as_ptr()).front = prev;
+            } else {
+                self.list.back = prev;
+                self.index = None;
+            }
+
+            self.cursor = next;
+            self.list.len -= 1;
+            Some(boxed_node.elem)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Clone for NodeRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Copy for NodeRef<T> {}
+
+#[cfg(feature = "std")]
+impl<T> PartialEq for NodeRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Eq for NodeRef<T> {}
+
+#[cfg(feature = "std")]
+impl<T> Hash for NodeRef<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
 }
 
 unsafe impl<T: Send> Send for LinkedList<T> {}
@@ -613,6 +879,11 @@ unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
 unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
 unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
 
+#[cfg(feature = "std")]
+unsafe impl<T: Send> Send for NodeRef<T> {}
+#[cfg(feature = "std")]
+unsafe impl<T: Sync> Sync for NodeRef<T> {}
+
 #[allow(dead_code)]
 fn assert_properties() {
     fn is_send<T: Send>() {}
@@ -642,10 +913,34 @@ fn assert_properties() {
         x
     }
 
-    /// ```compile_fail,E0308
-    /// use linked_list::IterMut;
+    /// The same function with matching lifetimes compiles, so variance is the
+    /// only thing the `compile_fail` below can trip on. It can't pin an error
+    /// code: rustc reports this as a borrowck error, which has none.
+    ///
+    /// ```
+    /// use unsafe_rust_deque::IterMut;
+    ///
+    /// fn iter_mut_same<'i, 'a, T>(x: IterMut<'i, &'a T>) -> IterMut<'i, &'a T> { x }
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use unsafe_rust_deque::IterMut;
     ///
     /// fn iter_mut_covariant<'i, 'a, T>(x: IterMut<'i, &'static T>) -> IterMut<'i, &'a T> { x }
-    /// `
+    /// ```
     fn iter_mut_invariant() {}
+
+    /// `back_mut` hands out `&mut T`, so like `front_mut` it needs `&mut self`.
+    ///
+    /// ```compile_fail,E0596
+    /// use unsafe_rust_deque::LinkedList;
+    ///
+    /// let mut list = LinkedList::new();
+    /// list.push_back(1);
+    /// let shared = &list;
+    /// let a = shared.back_mut().unwrap();
+    /// let b = shared.back().unwrap();
+    /// *a += *b;
+    /// ```
+    fn back_mut_exclusive() {}
 }
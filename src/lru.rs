@@ -0,0 +1,220 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::hash::Hash;
+
+use crate::cache::{Cache, CacheStats};
+use crate::linked_list::{self, LinkedList, NodeRef};
+
+// Entries live in the list most recently used first, the map only points at
+// their nodes so a hit can be relinked to the front without a walk.
+pub struct LruCache<K, V> {
+    list: LinkedList<(K, V)>,
+    map: HashMap<K, NodeRef<(K, V)>>,
+    cap: usize,
+    on_evict: Option<Box<dyn FnMut(K, V) + Send>>,
+    stats: CacheStats,
+}
+
+pub struct Iter<'a, K, V> {
+    iter: linked_list::Iter<'a, (K, V)>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    pub fn new(cap: usize) -> Self {
+        Self {
+            list: LinkedList::new(),
+            map: HashMap::new(),
+            cap,
+            on_evict: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn set_eviction_callback(&mut self, on_evict: impl FnMut(K, V) + Send + 'static) {
+        self.on_evict = Some(Box::new(on_evict));
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn cap(&self) -> usize {
+        self.cap
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = match self.map.get(key) {
+            Some(&node) => node,
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+
+        self.stats.hits += 1;
+        unsafe {
+            self.list.move_to_front(node);
+            Some(&mut self.list.node_mut(node).1)
+        }
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = *self.map.get(key)?;
+        unsafe { Some(&self.list.node(node).1) }
+    }
+
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.list.back().map(|(key, value)| (key, value))
+    }
+
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node) = self.map.get(&key) {
+            unsafe {
+                self.list.move_to_front(node);
+                return Some(std::mem::replace(&mut self.list.node_mut(node).1, value));
+            }
+        }
+
+        let node = self.list.push_front_node((key.clone(), value));
+        self.map.insert(key, node);
+        self.evict_to(self.cap);
+        None
+    }
+
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node = self.map.remove(key)?;
+        unsafe { Some(self.list.remove_node(node).1) }
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.list.pop_back()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    pub fn resize(&mut self, cap: usize) {
+        self.cap = cap;
+        self.evict_to(cap);
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.list.iter(),
+        }
+    }
+
+    fn evict_to(&mut self, cap: usize) {
+        while self.list.len() > cap {
+            let (key, value) = self.pop_lru().unwrap();
+            self.stats.evictions += 1;
+            if let Some(on_evict) = &mut self.on_evict {
+                on_evict(key, value);
+            }
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cache<K, V> for LruCache<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        LruCache::get(self, key)
+    }
+
+    fn put(&mut self, key: K, value: V) -> Option<V> {
+        LruCache::put(self, key, value)
+    }
+
+    fn len(&self) -> usize {
+        LruCache::len(self)
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.stats
+    }
+}
+
+impl<K: Debug, V: Debug> Debug for LruCache<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.list.iter().map(|(key, value)| (key, value)))
+            .finish()
+    }
+}
+
+impl<'a, K: Hash + Eq + Clone, V> IntoIterator for &'a LruCache<K, V> {
+    type IntoIter = Iter<'a, K, V>;
+    type Item = (&'a K, &'a V);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
@@ -1,15 +1,256 @@
-use std::process::Command;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::process::ExitCode;
 
-mod linked_list;
-#[cfg(test)]
-mod test;
+use unsafe_rust_deque::{CursorMut, LinkedList};
 
-fn main() {
-    let output = Command::new("cargo")
-        .arg("test")
-        .output()
-        .expect("Failed to run LinkedList tests");
+const HELP: &str = "\
+list commands:
+  push_front N | push_back N | pop_front | pop_back
+  append N...          push every N at the back
+  split_off AT         split the list at AT and print the back half
+  sort | clear
+cursor commands (optionally prefixed with `cursor`):
+  next | prev | ghost
+  insert_before N | insert_after N | remove
+  split_before | split_after
+  splice_before N... | splice_after N...
+other:
+  show | help | quit
 
-    let log = output.stdout;
-    println!("{}", String::from_utf8_lossy(&log));
+Lines starting with `#` are comments. After `sort` the cursor goes back to
+the ghost, list commands otherwise keep it on the same element.";
+
+// Reads commands from the file named on the command line, or from stdin, and
+// prints the list and cursor after each one. The cursor is kept as an index and
+// rebuilt for every command, since a live `CursorMut` would lock up the list.
+fn main() -> ExitCode {
+    let input: Box<dyn BufRead> = match env::args().nth(1) {
+        Some(path) => match File::open(&path) {
+            Ok(file) => Box::new(BufReader::new(file)),
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => Box::new(BufReader::new(io::stdin())),
+    };
+    let interactive = env::args().nth(1).is_none() && io::stdin().is_terminal();
+
+    let mut repl = Repl::default();
+    let mut failed = false;
+    if interactive {
+        println!("type `help` for commands");
+        prompt();
+    }
+    for (number, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("{}", err);
+                return ExitCode::FAILURE;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            if interactive {
+                prompt();
+            }
+            continue;
+        }
+        if !interactive {
+            println!("> {}", line);
+        }
+
+        match repl.run(line) {
+            Ok(Step::Quit) => break,
+            Ok(Step::Help) => println!("{}", HELP),
+            Ok(Step::Done(output)) => {
+                if let Some(output) = output {
+                    println!("= {}", output);
+                }
+                println!("{}", repl);
+            }
+            Err(err) => {
+                failed = true;
+                eprintln!("line {}: {}", number + 1, err);
+            }
+        }
+        if interactive {
+            prompt();
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn prompt() {
+    print!("> ");
+    let _ = io::stdout().flush();
+}
+
+enum Step {
+    Done(Option<String>),
+    Help,
+    Quit,
+}
+
+#[derive(Default)]
+struct Repl {
+    list: LinkedList<i64>,
+    // `None` is the ghost.
+    index: Option<usize>,
+}
+
+impl Repl {
+    fn run(&mut self, line: &str) -> Result<Step, String> {
+        let mut words = line.split_whitespace();
+        let mut command = words.next().unwrap_or_default();
+        if command == "cursor" {
+            command = words.next().ok_or("`cursor` needs a command")?;
+        }
+        let args = words
+            .map(|word| word.parse().map_err(|_| format!("not a number: {}", word)))
+            .collect::<Result<Vec<i64>, _>>()?;
+
+        let output = match (command, &args[..]) {
+            ("push_front", &[elem]) => {
+                self.list.push_front(elem);
+                self.index = self.index.map(|index| index + 1);
+                None
+            }
+            ("push_back", &[elem]) => {
+                self.list.push_back(elem);
+                None
+            }
+            ("pop_front", []) => {
+                self.index = self.index.and_then(|index| index.checked_sub(1));
+                Some(format!("{:?}", self.list.pop_front()))
+            }
+            ("pop_back", []) => {
+                if self.index.is_some_and(|index| index + 1 == self.list.len()) {
+                    self.index = None;
+                }
+                Some(format!("{:?}", self.list.pop_back()))
+            }
+            ("append", elems) => {
+                self.list.extend(elems.iter().copied());
+                None
+            }
+            ("split_off", &[at]) => {
+                let at = usize::try_from(at)
+                    .ok()
+                    .filter(|&at| at <= self.list.len())
+                    .ok_or_else(|| format!("split index {} out of 0..={}", at, self.list.len()))?;
+                if self.index.is_some_and(|index| index >= at) {
+                    self.index = None;
+                }
+                Some(format!("{:?}", self.list.split_off(at)))
+            }
+            ("sort", []) => {
+                self.list.sort();
+                self.index = None;
+                None
+            }
+            ("clear", []) => {
+                self.list.clear();
+                self.index = None;
+                None
+            }
+            ("next", []) => {
+                self.with_cursor(|cursor| cursor.move_next());
+                None
+            }
+            ("prev", []) => {
+                self.with_cursor(|cursor| cursor.move_prev());
+                None
+            }
+            ("ghost", []) => {
+                self.index = None;
+                None
+            }
+            ("insert_before", &[elem]) => {
+                self.with_cursor(|cursor| cursor.insert_before(elem));
+                None
+            }
+            ("insert_after", &[elem]) => {
+                self.with_cursor(|cursor| cursor.insert_after(elem));
+                None
+            }
+            ("remove", []) => {
+                let removed = self.with_cursor(|cursor| cursor.remove_current());
+                Some(format!("{:?}", removed))
+            }
+            ("split_before", []) => {
+                let split = self.with_cursor(|cursor| cursor.split_before());
+                Some(format!("{:?}", split))
+            }
+            ("split_after", []) => {
+                let split = self.with_cursor(|cursor| cursor.split_after());
+                Some(format!("{:?}", split))
+            }
+            ("splice_before", elems) => {
+                let input = elems.iter().copied().collect();
+                self.with_cursor(|cursor| cursor.splice_before(input));
+                None
+            }
+            ("splice_after", elems) => {
+                let input = elems.iter().copied().collect();
+                self.with_cursor(|cursor| cursor.splice_after(input));
+                None
+            }
+            ("show", []) => None,
+            ("help", []) => return Ok(Step::Help),
+            ("quit", []) => return Ok(Step::Quit),
+            (
+                "push_front" | "push_back" | "pop_front" | "pop_back" | "split_off" | "sort"
+                | "clear" | "next" | "prev" | "ghost" | "insert_before" | "insert_after" | "remove"
+                | "split_before" | "split_after" | "show" | "help" | "quit",
+                _,
+            ) => return Err(format!("wrong number of arguments to `{}`", command)),
+            _ => return Err(format!("unknown command `{}`, try `help`", command)),
+        };
+        Ok(Step::Done(output))
+    }
+
+    // Walks a fresh cursor out to `index`, runs `f` on it and remembers where it
+    // ended up.
+    fn with_cursor<R>(&mut self, f: impl FnOnce(&mut CursorMut<'_, i64>) -> R) -> R {
+        let mut cursor = self.list.cursor_mut();
+        if let Some(index) = self.index {
+            for _ in 0..=index {
+                cursor.move_next();
+            }
+        }
+        let result = f(&mut cursor);
+        self.index = cursor.index();
+        result
+    }
+}
+
+// The current element is marked with `<>`.
+impl std::fmt::Display for Repl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (i, elem) in self.list.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            if Some(i) == self.index {
+                write!(f, "<{}>", elem)?;
+            } else {
+                write!(f, "{}", elem)?;
+            }
+        }
+        write!(f, "]  len: {}  cursor: ", self.list.len())?;
+        match self.index {
+            Some(index) => write!(f, "{}", index),
+            None => write!(f, "ghost"),
+        }
+    }
 }
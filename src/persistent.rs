@@ -0,0 +1,415 @@
+// Both flavours share one body, the only difference is which pointer the
+// spine is built out of. The body refers to `Shared`, which each module
+// below aliases to its own pointer type.
+macro_rules! persistent_deque {
+    () => {
+        use alloc::vec::Vec;
+        use core::fmt::{self, Debug};
+        use core::iter::FromIterator;
+
+        type Stack<T> = Option<Shared<Cons<T>>>;
+
+        struct Cons<T> {
+            elem: Shared<T>,
+            next: Stack<T>,
+        }
+
+        // Two stacks meeting in the middle, `front` holds the first elements
+        // with the very first on top, `back` holds the last elements with the
+        // very last on top. Whenever there are at least two elements, both
+        // stacks are non-empty, so both ends are O(1) to read.
+        //
+        // When a push or pop would leave one stack empty, the other is split
+        // in half, which copies it: O(n). Used as a plain deque that's
+        // amortized O(1), since the next rebalance is at least n/2 pops away.
+        // Persistence breaks the amortization: popping the last element of a
+        // side from the same old version again and again pays the O(n) split
+        // every time. Making that O(1) needs memoized lazy rebalancing, and the
+        // `Arc` flavour has no thread-safe once-cell without `std`.
+        //
+        // `split_at` is O(k), k being how far `at` is from the end of the
+        // deque its stack holds, so splitting off a few elements at either
+        // end is cheap. A half that ends up with one stack empty is
+        // rebalanced as above, and `append` copies the shorter deque.
+        pub struct PersistentDeque<T> {
+            front: Stack<T>,
+            back: Stack<T>,
+            front_len: usize,
+            back_len: usize,
+        }
+
+        // The back stack runs the wrong way for a forward walk, so the first
+        // step into it collects it into `back_elems`, O(n) once. A walk that
+        // stops inside the front stack allocates nothing.
+        pub struct Iter<'a, T> {
+            front: Option<&'a Cons<T>>,
+            front_left: usize,
+            back: Option<&'a Cons<T>>,
+            back_elems: Vec<&'a T>,
+            len: usize,
+        }
+
+        fn cons<T>(elem: Shared<T>, next: Stack<T>) -> Stack<T> {
+            Some(Shared::new(Cons { elem, next }))
+        }
+
+        fn stack_iter<T>(stack: &Stack<T>) -> impl Iterator<Item = &Cons<T>> {
+            core::iter::successors(stack.as_deref(), |node| node.next.as_deref())
+        }
+
+        // Splits a stack holding every element of the deque so each side gets
+        // half. `near` keeps the stack's own orientation, `far` is reversed so
+        // its top is the element at the other end of the deque.
+        fn split_stack<T>(stack: &Stack<T>, len: usize) -> (Stack<T>, usize, Stack<T>, usize) {
+            let cells: Vec<&Shared<T>> = stack_iter(stack).map(|node| &node.elem).collect();
+            let near_len = len / 2;
+
+            let near = cells[..near_len]
+                .iter()
+                .rev()
+                .fold(None, |next, elem| cons(Shared::clone(elem), next));
+            let far = cells[near_len..]
+                .iter()
+                .fold(None, |next, elem| cons(Shared::clone(elem), next));
+
+            (near, near_len, far, len - near_len)
+        }
+
+        // Copies the top `n` cells of a stack into a new stack of their own,
+        // in the same order, and shares the rest.
+        fn cut<T>(stack: &Stack<T>, n: usize) -> (Stack<T>, Stack<T>) {
+            let mut top = Vec::with_capacity(n);
+            let mut rest = stack;
+            for _ in 0..n {
+                let node = rest.as_ref().unwrap();
+                top.push(&node.elem);
+                rest = &node.next;
+            }
+            let top = top
+                .into_iter()
+                .rev()
+                .fold(None, |next, elem| cons(Shared::clone(elem), next));
+            (top, rest.clone())
+        }
+
+        impl<T> PersistentDeque<T> {
+            pub fn new() -> Self {
+                Self {
+                    front: None,
+                    back: None,
+                    front_len: 0,
+                    back_len: 0,
+                }
+            }
+
+            fn balanced(
+                front: Stack<T>,
+                front_len: usize,
+                back: Stack<T>,
+                back_len: usize,
+            ) -> Self {
+                if front_len == 0 && back_len > 1 {
+                    let (back, back_len, front, front_len) = split_stack(&back, back_len);
+                    Self {
+                        front,
+                        back,
+                        front_len,
+                        back_len,
+                    }
+                } else if back_len == 0 && front_len > 1 {
+                    let (front, front_len, back, back_len) = split_stack(&front, front_len);
+                    Self {
+                        front,
+                        back,
+                        front_len,
+                        back_len,
+                    }
+                } else {
+                    Self {
+                        front,
+                        back,
+                        front_len,
+                        back_len,
+                    }
+                }
+            }
+
+            pub fn len(&self) -> usize {
+                self.front_len + self.back_len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+
+            pub fn ptr_eq(&self, other: &Self) -> bool {
+                fn same<T>(a: &Stack<T>, b: &Stack<T>) -> bool {
+                    match (a, b) {
+                        (Some(a), Some(b)) => Shared::ptr_eq(a, b),
+                        (None, None) => true,
+                        _ => false,
+                    }
+                }
+
+                same(&self.front, &other.front) && same(&self.back, &other.back)
+            }
+
+            pub fn front(&self) -> Option<&T> {
+                self.front
+                    .as_ref()
+                    .or(self.back.as_ref())
+                    .map(|node| &*node.elem)
+            }
+
+            pub fn back(&self) -> Option<&T> {
+                self.back
+                    .as_ref()
+                    .or(self.front.as_ref())
+                    .map(|node| &*node.elem)
+            }
+
+            pub fn push_front(&self, elem: T) -> Self {
+                Self::balanced(
+                    cons(Shared::new(elem), self.front.clone()),
+                    self.front_len + 1,
+                    self.back.clone(),
+                    self.back_len,
+                )
+            }
+
+            pub fn push_back(&self, elem: T) -> Self {
+                Self::balanced(
+                    self.front.clone(),
+                    self.front_len,
+                    cons(Shared::new(elem), self.back.clone()),
+                    self.back_len + 1,
+                )
+            }
+
+            pub fn pop_front(&self) -> Option<(&T, Self)> {
+                if let Some(node) = &self.front {
+                    let rest = Self::balanced(
+                        node.next.clone(),
+                        self.front_len - 1,
+                        self.back.clone(),
+                        self.back_len,
+                    );
+                    Some((&*node.elem, rest))
+                } else {
+                    self.back.as_ref().map(|node| (&*node.elem, Self::new()))
+                }
+            }
+
+            pub fn pop_back(&self) -> Option<(&T, Self)> {
+                if let Some(node) = &self.back {
+                    let rest = Self::balanced(
+                        self.front.clone(),
+                        self.front_len,
+                        node.next.clone(),
+                        self.back_len - 1,
+                    );
+                    Some((&*node.elem, rest))
+                } else {
+                    self.front.as_ref().map(|node| (&*node.elem, Self::new()))
+                }
+            }
+
+            pub fn append(&self, other: &Self) -> Self {
+                // Only the shorter side gets new cells, the longer one is
+                // shared with the result as-is.
+                if self.len() >= other.len() {
+                    let mut back = self.back.clone();
+                    for elem in other.shared_elems() {
+                        back = cons(Shared::clone(elem), back);
+                    }
+                    Self::balanced(
+                        self.front.clone(),
+                        self.front_len,
+                        back,
+                        self.back_len + other.len(),
+                    )
+                } else {
+                    let mut front = other.front.clone();
+                    for elem in self.shared_elems().into_iter().rev() {
+                        front = cons(Shared::clone(elem), front);
+                    }
+                    Self::balanced(
+                        front,
+                        other.front_len + self.len(),
+                        other.back.clone(),
+                        other.back_len,
+                    )
+                }
+            }
+
+            pub fn split_at(&self, at: usize) -> (Self, Self) {
+                assert!(
+                    at <= self.len(),
+                    "split index (is {}) should be <= len (is {})",
+                    at,
+                    self.len()
+                );
+
+                // Only the stack `at` falls in is walked, and only the cells
+                // between `at` and that stack's end of the deque are copied.
+                // Everything past `at` in it, and the whole other stack, is
+                // shared as-is.
+                if at <= self.front_len {
+                    let (head, rest) = cut(&self.front, at);
+                    (
+                        Self::balanced(head, at, None, 0),
+                        Self::balanced(rest, self.front_len - at, self.back.clone(), self.back_len),
+                    )
+                } else {
+                    let tail_len = self.len() - at;
+                    let (tail, rest) = cut(&self.back, tail_len);
+                    (
+                        Self::balanced(
+                            self.front.clone(),
+                            self.front_len,
+                            rest,
+                            self.back_len - tail_len,
+                        ),
+                        Self::balanced(None, 0, tail, tail_len),
+                    )
+                }
+            }
+
+            pub fn iter(&self) -> Iter<'_, T> {
+                Iter {
+                    front: self.front.as_deref(),
+                    front_left: self.front_len,
+                    back: self.back.as_deref(),
+                    back_elems: Vec::new(),
+                    len: self.len(),
+                }
+            }
+
+            fn shared_elems(&self) -> Vec<&Shared<T>> {
+                let mut elems: Vec<&Shared<T>> =
+                    stack_iter(&self.front).map(|node| &node.elem).collect();
+                let start = elems.len();
+                elems.extend(stack_iter(&self.back).map(|node| &node.elem));
+                elems[start..].reverse();
+                elems
+            }
+        }
+
+        impl<T> Drop for Cons<T> {
+            fn drop(&mut self) {
+                // Unlink uniquely owned cells one at a time instead of letting
+                // a long spine drop itself recursively.
+                let mut next = self.next.take();
+                while let Some(node) = next {
+                    match Shared::try_unwrap(node) {
+                        Ok(mut node) => next = node.next.take(),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+
+        impl<T> Clone for PersistentDeque<T> {
+            fn clone(&self) -> Self {
+                Self {
+                    front: self.front.clone(),
+                    back: self.back.clone(),
+                    front_len: self.front_len,
+                    back_len: self.back_len,
+                }
+            }
+        }
+
+        impl<T> Default for PersistentDeque<T> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<T> FromIterator<T> for PersistentDeque<T> {
+            fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+                let mut back = None;
+                let mut back_len = 0;
+                for elem in iter {
+                    back = cons(Shared::new(elem), back);
+                    back_len += 1;
+                }
+                Self::balanced(None, 0, back, back_len)
+            }
+        }
+
+        impl<T: Debug> Debug for PersistentDeque<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self).finish()
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for PersistentDeque<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.len() == other.len() && self.iter().eq(other)
+            }
+        }
+
+        impl<T: Eq> Eq for PersistentDeque<T> {}
+
+        impl<'a, T> IntoIterator for &'a PersistentDeque<T> {
+            type IntoIter = Iter<'a, T>;
+            type Item = &'a T;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<'a, T> Iterator for Iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.len == 0 {
+                    return None;
+                }
+
+                self.len -= 1;
+                if self.front_left > 0 {
+                    self.front_left -= 1;
+                    return self.front.map(|node| {
+                        self.front = node.next.as_deref();
+                        &*node.elem
+                    });
+                }
+
+                // Top of the back stack is the last element, so popping the
+                // collected cells gives them in order.
+                if let Some(back) = self.back.take() {
+                    self.back_elems =
+                        core::iter::successors(Some(back), |node| node.next.as_deref())
+                            .map(|node| &*node.elem)
+                            .collect();
+                }
+                self.back_elems.pop()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+            fn len(&self) -> usize {
+                self.len
+            }
+        }
+    };
+}
+
+pub mod rc {
+    use alloc::rc::Rc as Shared;
+
+    persistent_deque!();
+}
+
+pub mod sync {
+    use alloc::sync::Arc as Shared;
+
+    persistent_deque!();
+}
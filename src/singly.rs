@@ -0,0 +1,296 @@
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+pub mod queue;
+pub mod stack;
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+// Shared by `Stack` and `Queue`, so converting one into the other keeps every
+// node. `LinkedList`'s nodes carry a second link and can't be reused, so there
+// are no conversions to or from it: collecting makes the O(n) copy explicit.
+struct Node<T> {
+    next: Link<T>,
+    elem: T,
+}
+
+pub struct Iter<'a, T> {
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T> {
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+// A run of nodes cut out of a chain. `tail` may be None when it wasn't worth
+// finding, in which case whoever needs it walks there.
+struct Segment<T> {
+    head: Link<T>,
+    tail: Link<T>,
+    len: usize,
+}
+
+// The forward-only cursor behind both lists' `CursorMut`. It remembers the
+// node before the current one, which is all unlinking needs. Queues hand it
+// their tail to keep up to date; stacks don't have one.
+struct Cursor<'a, T> {
+    head: &'a mut Link<T>,
+    tail: Option<&'a mut Link<T>>,
+    len: &'a mut usize,
+    prev: Link<T>,
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+fn new_node<T>(elem: T, next: Link<T>) -> NonNull<Node<T>> {
+    unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Node { next, elem }))) }
+}
+
+// Safety: `node` must be the last link to a node from `new_node`.
+unsafe fn free_node<T>(node: NonNull<Node<T>>) -> (T, Link<T>) {
+    let boxed = Box::from_raw(node.as_ptr());
+    (boxed.elem, boxed.next)
+}
+
+unsafe fn free_chain<T>(mut head: Link<T>) {
+    while let Some(node) = head {
+        head = free_node(node).1;
+    }
+}
+
+unsafe fn last<T>(mut node: NonNull<Node<T>>) -> NonNull<Node<T>> {
+    while let Some(next) = (*node.as_ptr()).next {
+        node = next;
+    }
+    node
+}
+
+// Reverses a chain in place and returns the new head. The old head is the
+// new tail.
+unsafe fn reverse<T>(mut head: Link<T>) -> Link<T> {
+    let mut reversed = None;
+    while let Some(node) = head {
+        head = (*node.as_ptr()).next;
+        (*node.as_ptr()).next = reversed;
+        reversed = Some(node);
+    }
+    reversed
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(next: Link<T>, len: usize) -> Self {
+        Self {
+            next,
+            len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> IterMut<'a, T> {
+    fn new(next: Link<T>, len: usize) -> Self {
+        Self {
+            next,
+            len,
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = (*node.as_ptr()).next;
+            &(*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|node| unsafe {
+            self.len -= 1;
+            self.next = (*node.as_ptr()).next;
+            &mut (*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    fn new(head: &'a mut Link<T>, tail: Option<&'a mut Link<T>>, len: &'a mut usize) -> Self {
+        Self {
+            head,
+            tail,
+            len,
+            prev: None,
+            current: None,
+            index: None,
+        }
+    }
+
+    fn set_tail(&mut self, node: Link<T>) {
+        if let Some(tail) = &mut self.tail {
+            **tail = node;
+        }
+    }
+
+    fn move_to_ghost(&mut self) {
+        self.prev = None;
+        self.current = None;
+        self.index = None;
+    }
+
+    fn move_next(&mut self) {
+        match self.current {
+            Some(node) => unsafe {
+                self.prev = self.current;
+                self.current = (*node.as_ptr()).next;
+                self.index = self.index.map(|index| index + 1);
+                if self.current.is_none() {
+                    self.move_to_ghost();
+                }
+            },
+            None => {
+                self.current = *self.head;
+                self.index = self.current.map(|_| 0);
+            }
+        }
+    }
+
+    fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                Some(node) => (*node.as_ptr()).next,
+                None => *self.head,
+            };
+            next.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    fn insert_after(&mut self, elem: T) {
+        let node = new_node(elem, None);
+        self.splice_after(Segment {
+            head: Some(node),
+            tail: Some(node),
+            len: 1,
+        });
+    }
+
+    // Moves on to the next element, or the ghost if there isn't one.
+    fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            let (elem, next) = free_node(node);
+            match self.prev {
+                Some(prev) => (*prev.as_ptr()).next = next,
+                None => *self.head = next,
+            }
+            *self.len -= 1;
+
+            self.current = next;
+            if next.is_none() {
+                self.set_tail(self.prev);
+                self.move_to_ghost();
+            }
+            Some(elem)
+        }
+    }
+
+    // Everything after the current element, or everything at all from the
+    // ghost. Stacks don't learn the tail of what they split off.
+    fn split_after(&mut self) -> Segment<T> {
+        match self.current {
+            Some(node) => unsafe {
+                let head = (*node.as_ptr()).next.take();
+                let len = *self.len - self.index.unwrap() - 1;
+                *self.len -= len;
+
+                let mut tail = None;
+                if head.is_some() {
+                    if let Some(old) = &mut self.tail {
+                        tail = old.replace(node);
+                    }
+                }
+                Segment { head, tail, len }
+            },
+            None => {
+                let tail = self.tail.as_mut().and_then(|tail| tail.take());
+                Segment {
+                    head: self.head.take(),
+                    tail,
+                    len: core::mem::take(self.len),
+                }
+            }
+        }
+    }
+
+    // From the ghost this puts `input` at the front.
+    fn splice_after(&mut self, input: Segment<T>) {
+        let Some(input_head) = input.head else {
+            return;
+        };
+
+        unsafe {
+            let input_tail = match input.tail {
+                Some(tail) => tail,
+                None => last(input_head),
+            };
+
+            match self.current {
+                Some(node) => {
+                    (*input_tail.as_ptr()).next = (*node.as_ptr()).next;
+                    (*node.as_ptr()).next = Some(input_head);
+                }
+                None => {
+                    (*input_tail.as_ptr()).next = *self.head;
+                    *self.head = Some(input_head);
+                }
+            }
+
+            if (*input_tail.as_ptr()).next.is_none() {
+                self.set_tail(Some(input_tail));
+            }
+            *self.len += input.len;
+        }
+    }
+}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
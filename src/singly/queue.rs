@@ -0,0 +1,289 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use super::stack::Stack;
+use super::{free_chain, free_node, last, new_node, reverse, Cursor, Iter, IterMut, Link, Segment};
+
+// Pops at the front and pushes at either end. With one link per node there's
+// no way back from the tail, so no `pop_back`.
+pub struct Queue<T> {
+    pub(super) head: Link<T>,
+    pub(super) tail: Link<T>,
+    pub(super) len: usize,
+    _boo: PhantomData<T>,
+}
+
+pub struct IntoIter<T> {
+    queue: Queue<T>,
+}
+
+pub struct CursorMut<'a, T> {
+    cursor: Cursor<'a, T>,
+}
+
+impl<T> Queue<T> {
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            tail: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let new = new_node(elem, self.head);
+        if self.tail.is_none() {
+            self.tail = Some(new);
+        }
+        self.head = Some(new);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let new = new_node(elem, None);
+        match self.tail {
+            Some(old) => unsafe { (*old.as_ptr()).next = Some(new) },
+            None => self.head = Some(new),
+        }
+        self.tail = Some(new);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe {
+            let (elem, next) = free_node(node);
+            self.head = next;
+            if next.is_none() {
+                self.tail = None;
+            }
+            self.len -= 1;
+            elem
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.tail.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.tail.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { free_chain(self.head.take()) };
+        self.tail = None;
+        self.len = 0;
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+        match self.tail {
+            Some(tail) => unsafe { (*tail.as_ptr()).next = Some(other_head) },
+            None => self.head = Some(other_head),
+        }
+        self.tail = other.tail.take();
+        self.len += core::mem::take(&mut other.len);
+    }
+
+    pub fn reverse(&mut self) {
+        self.tail = self.head;
+        self.head = unsafe { reverse(self.head) };
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.head, self.len)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.head, self.len)
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cursor: Cursor::new(&mut self.head, Some(&mut self.tail), &mut self.len),
+        }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for Queue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for Queue<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for Queue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Queue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+// Keeps every node, but has to walk the stack to find its tail.
+impl<T> From<Stack<T>> for Queue<T> {
+    fn from(mut stack: Stack<T>) -> Self {
+        let head = stack.head.take();
+        Self {
+            head,
+            tail: head.map(|head| unsafe { last(head) }),
+            len: core::mem::take(&mut stack.len),
+            _boo: PhantomData,
+        }
+    }
+}
+
+impl<T: Debug> Debug for Queue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Queue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for Queue<T> {}
+
+impl<T: Hash> Hash for Queue<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state)
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Queue<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Queue<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for Queue<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { queue: self }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.queue.len, Some(self.queue.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.queue.len
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cursor.index
+    }
+
+    pub fn move_next(&mut self) {
+        self.cursor.move_next();
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cursor.current()
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.cursor.peek_next()
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        self.cursor.insert_after(elem);
+    }
+
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.cursor.remove_current()
+    }
+
+    pub fn split_after(&mut self) -> Queue<T> {
+        let segment = self.cursor.split_after();
+        Queue {
+            head: segment.head,
+            tail: segment.tail,
+            len: segment.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn splice_after(&mut self, mut input: Queue<T>) {
+        let segment = Segment {
+            head: input.head.take(),
+            tail: input.tail.take(),
+            len: core::mem::take(&mut input.len),
+        };
+        self.cursor.splice_after(segment);
+    }
+}
+
+unsafe impl<T: Send> Send for Queue<T> {}
+unsafe impl<T: Sync> Sync for Queue<T> {}
@@ -0,0 +1,264 @@
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use super::queue::Queue;
+use super::{free_chain, free_node, new_node, reverse, Cursor, Iter, IterMut, Link, Segment};
+
+// Only ever touched at the front, so it keeps no tail pointer. Pushing goes on
+// top, which makes `Extend` and `FromIterator` reverse their input; converting
+// from a `Queue` keeps the front at the front.
+pub struct Stack<T> {
+    pub(super) head: Link<T>,
+    pub(super) len: usize,
+    _boo: PhantomData<T>,
+}
+
+pub struct IntoIter<T> {
+    stack: Stack<T>,
+}
+
+pub struct CursorMut<'a, T> {
+    cursor: Cursor<'a, T>,
+}
+
+impl<T> Stack<T> {
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        self.head = Some(new_node(elem, self.head));
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.head.map(|node| unsafe {
+            let (elem, next) = free_node(node);
+            self.head = next;
+            self.len -= 1;
+            elem
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.head.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.head.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn clear(&mut self) {
+        unsafe { free_chain(self.head.take()) };
+        self.len = 0;
+    }
+
+    pub fn reverse(&mut self) {
+        self.head = unsafe { reverse(self.head) };
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.head, self.len)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.head, self.len)
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            cursor: Cursor::new(&mut self.head, None, &mut self.len),
+        }
+    }
+
+    fn from_segment(segment: Segment<T>) -> Self {
+        Self {
+            head: segment.head,
+            len: segment.len,
+            _boo: PhantomData,
+        }
+    }
+
+    // In order, front first, unlike `FromIterator`.
+    fn from_ordered<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        let mut cursor = stack.cursor_mut();
+        for elem in iter {
+            cursor.insert_after(elem);
+            cursor.move_next();
+        }
+        stack
+    }
+}
+
+impl<T> Drop for Stack<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for Stack<T> {
+    fn clone(&self) -> Self {
+        Self::from_ordered(self.iter().cloned())
+    }
+}
+
+impl<T> Extend<T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_front(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for Stack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+// Keeps every node, the queue just drops its tail pointer.
+impl<T> From<Queue<T>> for Stack<T> {
+    fn from(mut queue: Queue<T>) -> Self {
+        let stack = Self {
+            head: queue.head.take(),
+            len: queue.len,
+            _boo: PhantomData,
+        };
+        queue.tail = None;
+        queue.len = 0;
+        stack
+    }
+}
+
+impl<T: Debug> Debug for Stack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for Stack<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for Stack<T> {}
+
+impl<T: Hash> Hash for Stack<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state)
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Stack<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Stack<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for Stack<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { stack: self }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stack.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.stack.len, Some(self.stack.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.stack.len
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.cursor.index
+    }
+
+    pub fn move_next(&mut self) {
+        self.cursor.move_next();
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.cursor.current()
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.cursor.peek_next()
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        self.cursor.insert_after(elem);
+    }
+
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.cursor.remove_current()
+    }
+
+    pub fn split_after(&mut self) -> Stack<T> {
+        Stack::from_segment(self.cursor.split_after())
+    }
+
+    // Walks `input` to find its last node.
+    pub fn splice_after(&mut self, mut input: Stack<T>) {
+        let segment = Segment {
+            head: input.head.take(),
+            tail: None,
+            len: core::mem::take(&mut input.len),
+        };
+        self.cursor.splice_after(segment);
+    }
+}
+
+unsafe impl<T: Send> Send for Stack<T> {}
+unsafe impl<T: Sync> Sync for Stack<T> {}
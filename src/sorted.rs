@@ -0,0 +1,234 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::iter::{Skip, Take};
+use core::ops::{Bound, RangeBounds};
+
+use crate::linked_list::{IntoIter, Iter, LinkedList};
+
+// Ascending, with equal elements kept in the order they were inserted.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortedList<T> {
+    list: LinkedList<T>,
+}
+
+pub struct Range<'a, T> {
+    iter: Take<Skip<Iter<'a, T>>>,
+}
+
+impl<T: Ord> SortedList<T> {
+    pub fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.list.front()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.list.back()
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    // Goes after any equal elements and returns where it ended up. Walks from
+    // the back, so mostly ascending input stays cheap.
+    pub fn insert(&mut self, value: T) -> usize {
+        let mut cursor = self.list.cursor_mut();
+        cursor.move_prev();
+        while cursor.current().is_some_and(|elem| *elem > value) {
+            cursor.move_prev();
+        }
+
+        let index = cursor.index().map_or(0, |index| index + 1);
+        cursor.insert_after(value);
+        index
+    }
+
+    // Removes the first element equal to `value`.
+    pub fn remove<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut cursor = self.list.cursor_mut();
+        cursor.move_next();
+        while let Some(elem) = cursor.current() {
+            match (*elem).borrow().cmp(value) {
+                Ordering::Less => cursor.move_next(),
+                Ordering::Equal => return cursor.remove_current(),
+                Ordering::Greater => break,
+            }
+        }
+        None
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.list
+            .iter()
+            .map(Borrow::borrow)
+            .take_while(|elem| *elem <= value)
+            .any(|elem| elem == value)
+    }
+
+    // How many elements are less than `value`.
+    pub fn rank<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.list
+            .iter()
+            .take_while(|elem| (*elem).borrow() < value)
+            .count()
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let below = |elem: &Q| match range.start_bound() {
+            Bound::Included(start) => elem < start,
+            Bound::Excluded(start) => elem <= start,
+            Bound::Unbounded => false,
+        };
+        let above = |elem: &Q| match range.end_bound() {
+            Bound::Included(end) => elem > end,
+            Bound::Excluded(end) => elem >= end,
+            Bound::Unbounded => false,
+        };
+
+        let (mut skip, mut take) = (0, 0);
+        for elem in self.list.iter().map(Borrow::borrow) {
+            if above(elem) {
+                break;
+            }
+            if below(elem) {
+                skip += 1;
+            } else {
+                take += 1;
+            }
+        }
+
+        Range {
+            iter: self.list.iter().skip(skip).take(take),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.list.iter()
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn as_list(&self) -> &LinkedList<T> {
+        &self.list
+    }
+
+    pub fn into_list(self) -> LinkedList<T> {
+        self.list
+    }
+}
+
+impl<T> Default for SortedList<T> {
+    fn default() -> Self {
+        Self {
+            list: LinkedList::new(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for SortedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.list.fmt(f)
+    }
+}
+
+// Sorts the batch on its own, then merges it in with a single pass over the
+// list. Incoming elements go after equal ones that were already here.
+impl<T: Ord> Extend<T> for SortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut batch: Vec<T> = iter.into_iter().collect();
+        batch.sort();
+
+        let mut cursor = self.list.cursor_mut();
+        cursor.move_next();
+        for value in batch {
+            while cursor.current().is_some_and(|elem| *elem <= value) {
+                cursor.move_next();
+            }
+            cursor.insert_before(value);
+        }
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> IntoIterator for SortedList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Range<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Range<'a, T> {}
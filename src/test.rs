@@ -1,4 +1,50 @@
+// Tests always have `std` to run on, so it's pulled in here even when the
+// library itself is built without it. Only the tests for std-only modules are
+// left out then.
+extern crate std;
+
+use std::prelude::rust_2021::*;
+use std::{format, vec};
+
+use crate::array_list::ArrayLinkedList;
+#[cfg(feature = "std")]
+use crate::bounded::SyncBoundedDeque;
+use crate::bounded::{BoundedDeque, OverflowPolicy};
+#[cfg(feature = "std")]
+use crate::cache::arc::ArcCache;
+#[cfg(feature = "std")]
+use crate::cache::lfu::LfuCache;
+#[cfg(feature = "std")]
+use crate::cache::slru::SlruCache;
+#[cfg(feature = "std")]
+use crate::cache::{self, Cache, CacheStats};
+#[cfg(feature = "std")]
+use crate::channel::{self, RecvTimeoutError, TryRecvError, TrySendError};
+use crate::cow::CowList;
+use crate::deque::algorithms;
+use crate::deque::{CursorDeque, Deque, DequeCursor, SplittableDeque};
+#[cfg(feature = "std")]
+use crate::expiring::{Clock, ExpiringDeque, ManualClock};
+use crate::indexed::IndexedList;
+use crate::journal::Journal;
+#[cfg(feature = "std")]
+use crate::linked_hash::{Entry, LinkedHashMap, LinkedHashSet};
 use crate::linked_list::LinkedList;
+#[cfg(feature = "std")]
+use crate::lru::LruCache;
+use crate::persistent;
+use crate::singly::queue::Queue;
+use crate::singly::stack::Stack;
+use crate::sorted::SortedList;
+use crate::text::TextBuffer;
+#[cfg(feature = "std")]
+use crate::timer_wheel::TimerWheel;
+use crate::tree_list::TreeList;
+use crate::weighted::{ByteLen, OverBudget, WeightPolicy, WeightedDeque};
+use crate::window::{AggregateWindow, MonotonicDeque, Natural, Reversed, SlidingWindow};
+#[cfg(feature = "std")]
+use crate::work_stealing::Worker;
+use crate::xor::XorLinkedList;
 
 fn generate_test() -> LinkedList<i32> {
     list_from(&[0, 1, 2, 3, 4, 5, 6])
@@ -121,6 +167,7 @@ fn test_iterator_double_end() {
 }
 
 #[test]
+#[allow(clippy::manual_next_back)]
 fn test_rev_iter() {
     let m = generate_test();
 
@@ -203,6 +250,7 @@ fn test_ord() {
 }
 
 #[test]
+#[allow(clippy::neg_cmp_op_on_partial_ord, clippy::zero_divided_by_zero)]
 fn test_ord_nan() {
     let nan = 0.0f64 / 0.0;
     let n = list_from(&[nan]);
@@ -236,6 +284,7 @@ fn test_ord_nan() {
 }
 
 #[test]
+#[allow(clippy::useless_vec)]
 fn test_debug() {
     let list: LinkedList<i32> = (0..10).collect();
     assert_eq!(format!("{:?}", list), "[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]");
@@ -340,7 +389,6 @@ fn test_cursor_mut_insert() {
         &[10, 7, 1, 8, 2, 3, 4, 5, 6, 9],
     );
 
-    /* remove_current not impl'd
     let mut cursor = m.cursor_mut();
     cursor.move_next();
     cursor.move_prev();
@@ -355,8 +403,10 @@ fn test_cursor_mut_insert() {
     cursor.move_next();
     assert_eq!(cursor.remove_current(), Some(10));
     check_links(&m);
-    assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 8, 2, 3, 4, 5, 6]);
-    */
+    assert_eq!(
+        m.iter().cloned().collect::<Vec<_>>(),
+        &[1, 8, 2, 3, 4, 5, 6]
+    );
 
     let mut m: LinkedList<u32> = LinkedList::new();
     m.extend([1, 8, 2, 3, 4, 5, 6]);
@@ -399,10 +449,2701 @@ fn test_cursor_mut_insert() {
     );
 }
 
-fn check_links<T: Eq + std::fmt::Debug>(list: &LinkedList<T>) {
-    let from_front: Vec<_> = list.iter().collect();
-    let from_back: Vec<_> = list.iter().rev().collect();
+#[test]
+fn test_cursor_split_at_ends() {
+    let mut m = generate_test();
+    let mut cursor = m.cursor_mut();
+    cursor.move_next();
+    assert!(cursor.split_before().is_empty());
+    cursor.move_prev();
+    cursor.move_prev();
+    assert!(cursor.split_after().is_empty());
+    assert_eq!(cursor.current(), Some(&mut 6));
+    check_links(&m);
+    assert_eq!(m, generate_test());
+}
+
+#[test]
+fn test_sort() {
+    let mut list = list_from(&[5, 3, 9, 1, 3, 7, 0, 8]);
+    list.sort();
+    check_links(&list);
+    assert_eq!(
+        list.iter().cloned().collect::<Vec<_>>(),
+        &[0, 1, 3, 3, 5, 7, 8, 9]
+    );
+
+    // Ties keep their order.
+    let mut list = list_from(&[(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]);
+    list.sort_by(|a, b| a.0.cmp(&b.0));
+    check_links(&list);
+    assert_eq!(list.iter().map(|x| x.1).collect::<String>(), "ebdac");
+
+    let mut seed = 7u64;
+    let mut list: LinkedList<_> = (0..1000)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % 100
+        })
+        .collect();
+    let mut expected: Vec<_> = list.iter().cloned().collect();
+    expected.sort();
+    list.sort_by(|a, b| b.cmp(a));
+    list.sort();
+    check_links(&list);
+    assert_eq!(list.len(), 1000);
+    assert_eq!(list.iter().cloned().collect::<Vec<_>>(), expected);
+
+    // A panicking comparison leaves every element in the list.
+    let mut list = list_from(&[4, 2, 6, 1, 5, 3]);
+    let mut calls = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.sort_by(|a, b| {
+            calls += 1;
+            assert!(calls < 5);
+            a.cmp(b)
+        })
+    }));
+    assert!(result.is_err());
+    check_links(&list);
+    let mut rest: Vec<_> = list.iter().cloned().collect();
+    rest.sort();
+    assert_eq!(rest, &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(list.len(), 6);
+
+    let mut list: LinkedList<i32> = LinkedList::new();
+    list.sort();
+    assert!(list.is_empty());
+}
+
+// Any list whose iterator runs both ways: walking back from the back has to
+// meet every node walking forward from the front did.
+fn check_links<'a, T, L>(list: &'a L)
+where
+    T: Eq + std::fmt::Debug + 'a,
+    &'a L: IntoIterator<Item = &'a T>,
+    <&'a L as IntoIterator>::IntoIter: DoubleEndedIterator,
+{
+    let from_front: Vec<_> = list.into_iter().collect();
+    let from_back: Vec<_> = list.into_iter().rev().collect();
     let re_reversed: Vec<_> = from_back.into_iter().rev().collect();
 
     assert_eq!(from_front, re_reversed);
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn test_work_stealing_ends() {
+    let worker = Worker::new();
+    let stealer = worker.stealer();
+
+    assert_eq!(worker.pop(), None);
+    assert_eq!(stealer.steal(), None);
+
+    worker.push(1);
+    worker.push(2);
+    worker.push(3);
+    assert_eq!(worker.len(), 3);
+    assert_eq!(worker.pop(), Some(3));
+    assert_eq!(stealer.steal(), Some(1));
+    assert_eq!(stealer.clone().steal(), Some(2));
+    assert!(stealer.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_work_stealing_half() {
+    let worker = Worker::new();
+    let stealer = worker.stealer();
+    assert!(stealer.steal_half().is_empty());
+
+    for i in 0..7 {
+        worker.push(i);
+    }
+    let batch = stealer.steal_half();
+    check_links(&batch);
+    assert_eq!(batch.into_iter().collect::<Vec<_>>(), &[0, 1, 2, 3]);
+    assert_eq!(worker.len(), 3);
+
+    let thief = Worker::new();
+    assert_eq!(stealer.steal_half_into(&thief), Some(4));
+    assert_eq!(thief.pop(), Some(5));
+    assert_eq!(worker.pop(), Some(6));
+    assert_eq!(stealer.steal_half().into_iter().collect::<Vec<_>>(), &[]);
+
+    worker.push(10);
+    assert_eq!(stealer.steal_half().into_iter().collect::<Vec<_>>(), &[10]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_work_stealing_contention() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    const ITEMS: usize = 10_000;
+
+    let worker = Worker::new();
+    let done = Arc::new(AtomicBool::new(false));
+
+    let thieves: Vec<_> = (0..4)
+        .map(|i| {
+            let stealer = worker.stealer();
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                let mut seen = Vec::new();
+                loop {
+                    let batch = if i % 2 == 0 {
+                        stealer.steal().into_iter().collect()
+                    } else {
+                        stealer.steal_half()
+                    };
+                    if batch.is_empty() && done.load(Ordering::Acquire) && stealer.is_empty() {
+                        break;
+                    }
+                    seen.extend(batch);
+                }
+                seen
+            })
+        })
+        .collect();
+
+    let mut seen = Vec::new();
+    for i in 0..ITEMS {
+        worker.push(i);
+        if i % 3 == 0 {
+            seen.extend(worker.pop());
+        }
+    }
+    done.store(true, Ordering::Release);
+
+    for thief in thieves {
+        seen.extend(thief.join().unwrap());
+    }
+    seen.extend(std::iter::from_fn(|| worker.pop()));
+
+    seen.sort_unstable();
+    assert_eq!(seen, (0..ITEMS).collect::<Vec<_>>());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_work_stealing_thread_pool() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    const WORKERS: usize = 4;
+    const TASKS: usize = 1_000;
+
+    let workers: Vec<Worker<usize>> = (0..WORKERS).map(|_| Worker::new()).collect();
+    let stealers: Vec<_> = workers.iter().map(Worker::stealer).collect();
+
+    // Everything lands on one queue so the rest of the pool has to steal.
+    for task in 0..TASKS {
+        workers[0].push(task);
+    }
+
+    let remaining = Arc::new(AtomicUsize::new(TASKS));
+    let total = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = workers
+        .into_iter()
+        .enumerate()
+        .map(|(id, local)| {
+            let stealers = stealers.clone();
+            let remaining = Arc::clone(&remaining);
+            let total = Arc::clone(&total);
+            thread::spawn(move || {
+                let mut ran = 0;
+                while remaining.load(Ordering::Acquire) > 0 {
+                    let task = local.pop().or_else(|| {
+                        (1..WORKERS)
+                            .map(|offset| &stealers[(id + offset) % WORKERS])
+                            .find_map(|victim| victim.steal_half_into(&local))
+                    });
+
+                    match task {
+                        Some(task) => {
+                            total.fetch_add(task, Ordering::Relaxed);
+                            remaining.fetch_sub(1, Ordering::AcqRel);
+                            ran += 1;
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+                ran
+            })
+        })
+        .collect();
+
+    let ran: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    assert_eq!(ran, TASKS);
+    assert_eq!(total.load(Ordering::Relaxed), (0..TASKS).sum());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_channel_unbounded() {
+    let (tx, rx) = channel::channel();
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    let tx2 = tx.clone();
+    tx2.send(3).unwrap();
+    assert_eq!(rx.len(), 3);
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), &[2, 3]);
+
+    drop(tx);
+    assert!(!rx.is_disconnected());
+    tx2.send(4).unwrap();
+    drop(tx2);
+    assert!(rx.is_disconnected());
+    assert_eq!(rx.recv(), Ok(4));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    assert!(rx.recv().is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_channel_receiver_dropped() {
+    let (tx, rx) = channel::sync_channel(1);
+    tx.send(1).unwrap();
+    assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+    drop(rx);
+    assert!(tx.is_disconnected());
+    assert_eq!(tx.send(3).unwrap_err().0, 3);
+    assert_eq!(tx.try_send(4), Err(TrySendError::Disconnected(4)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_channel_recv_all() {
+    let (tx, rx) = channel::channel();
+    assert!(rx.try_recv_all().is_empty());
+
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    tx.send(3).unwrap();
+    let batch = rx.recv_all().unwrap();
+    check_links(&batch);
+    assert_eq!(batch.into_iter().collect::<Vec<_>>(), &[1, 2, 3]);
+    assert!(rx.is_empty());
+
+    tx.send(4).unwrap();
+    drop(tx);
+    assert_eq!(rx.try_recv_all().into_iter().collect::<Vec<_>>(), &[4]);
+    assert!(rx.recv_all().is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_channel_recv_timeout() {
+    use std::thread;
+    use std::time::Duration;
+
+    let (tx, rx) = channel::channel();
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(10)),
+        Err(RecvTimeoutError::Timeout)
+    );
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(7).unwrap();
+    });
+    assert_eq!(rx.recv_timeout(Duration::from_secs(10)), Ok(7));
+    handle.join().unwrap();
+    assert_eq!(
+        rx.recv_timeout(Duration::from_secs(10)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+
+    // Too far out for an `Instant`, so it just blocks.
+    let (tx, rx) = channel::channel();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(8).unwrap();
+    });
+    assert_eq!(rx.recv_timeout(Duration::MAX), Ok(8));
+    handle.join().unwrap();
+    assert_eq!(
+        rx.recv_timeout(Duration::MAX),
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_channel_bounded_producers() {
+    use std::thread;
+
+    let (tx, rx) = channel::sync_channel(4);
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in 0..250 {
+                    tx.send(p * 1000 + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+
+    let mut received = Vec::new();
+    while let Ok(batch) = rx.recv_all() {
+        assert!(batch.len() <= 4);
+        received.extend(batch);
+    }
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    for p in 0..4 {
+        let from_p: Vec<_> = received.iter().filter(|&&x| x / 1000 == p).collect();
+        assert_eq!(from_p.len(), 250);
+        assert!(from_p.windows(2).all(|w| w[0] < w[1]));
+    }
+}
+
+#[test]
+fn test_persistent_versions() {
+    use persistent::rc::PersistentDeque;
+
+    let empty = PersistentDeque::new();
+    assert_eq!(empty.pop_front(), None);
+    assert_eq!(empty.pop_back(), None);
+
+    let one = empty.push_back(1);
+    let two = one.push_back(2);
+    let three = two.push_front(0);
+    assert_eq!(empty.len(), 0);
+    assert_eq!(one.iter().copied().collect::<Vec<_>>(), &[1]);
+    assert_eq!(two.iter().copied().collect::<Vec<_>>(), &[1, 2]);
+    assert_eq!(three.iter().copied().collect::<Vec<_>>(), &[0, 1, 2]);
+    assert_eq!(three.front(), Some(&0));
+    assert_eq!(three.back(), Some(&2));
+
+    let (first, rest) = three.pop_front().unwrap();
+    assert_eq!(*first, 0);
+    assert_eq!(rest, two);
+    let (last, rest) = rest.pop_back().unwrap();
+    assert_eq!(*last, 2);
+    assert_eq!(rest, one);
+
+    let (last, rest) = one.pop_back().unwrap();
+    assert_eq!(*last, 1);
+    assert!(rest.is_empty());
+    assert_eq!(three.len(), 3);
+
+    let copy = three.clone();
+    assert!(copy.ptr_eq(&three));
+    assert!(!copy.push_back(3).ptr_eq(&three));
+}
+
+#[test]
+fn test_persistent_drain_both_ends() {
+    use persistent::rc::PersistentDeque;
+
+    let full: PersistentDeque<i32> = (0..100).collect();
+    let mut version = full.clone();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    while let Some((elem, rest)) = version.pop_front() {
+        front.push(*elem);
+        version = rest;
+        if let Some((elem, rest)) = version.pop_back() {
+            back.push(*elem);
+            version = rest;
+        }
+    }
+    back.reverse();
+    front.extend(back);
+    assert_eq!(front, (0..100).collect::<Vec<_>>());
+    assert_eq!(full.iter().copied().collect::<Vec<_>>(), front);
+}
+
+#[test]
+fn test_persistent_append_split() {
+    use persistent::rc::PersistentDeque;
+
+    let left: PersistentDeque<i32> = (0..3).collect();
+    let right: PersistentDeque<i32> = (3..10).collect();
+    let joined = left.append(&right);
+    assert_eq!(
+        joined.iter().copied().collect::<Vec<_>>(),
+        (0..10).collect::<Vec<_>>()
+    );
+    assert_eq!(right.append(&left).len(), 10);
+    assert_eq!(joined.append(&PersistentDeque::new()), joined);
+
+    for at in 0..=10 {
+        let (head, tail) = joined.split_at(at);
+        assert_eq!(
+            head.iter().copied().collect::<Vec<_>>(),
+            (0..at as i32).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            tail.iter().copied().collect::<Vec<_>>(),
+            (at as i32..10).collect::<Vec<_>>()
+        );
+        assert_eq!(head.append(&tail), joined);
+    }
+    assert_eq!(joined.len(), 10);
+}
+
+#[test]
+fn test_persistent_arc_threads() {
+    use persistent::sync::PersistentDeque;
+    use std::thread;
+
+    let base: PersistentDeque<String> = (0..10).map(|i| i.to_string()).collect();
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let base = base.clone();
+            thread::spawn(move || {
+                base.push_back(format!("t{}", i))
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let seen = handle.join().unwrap();
+        assert_eq!(seen.len(), 11);
+        assert_eq!(seen[10], format!("t{}", i));
+    }
+    assert_eq!(base.len(), 10);
+}
+
+#[test]
+fn test_persistent_long_spine_drop() {
+    use persistent::rc::PersistentDeque;
+
+    let mut list = PersistentDeque::new();
+    for i in 0..200_000 {
+        list = list.push_back(i);
+    }
+    assert_eq!(list.back(), Some(&199_999));
+    drop(list);
+}
+
+#[test]
+fn test_cow_shares_until_write() {
+    let original: CowList<i32> = (0..5).collect();
+    let mut reader = original.clone();
+    assert!(reader.ptr_eq(&original));
+    assert!(!original.is_unique());
+    assert_eq!(reader.len(), 5);
+    assert_eq!(reader.front(), Some(&0));
+
+    reader.push_back(5);
+    assert!(!reader.ptr_eq(&original));
+    assert!(reader.is_unique() && original.is_unique());
+    assert_eq!(
+        original.iter().copied().collect::<Vec<_>>(),
+        &[0, 1, 2, 3, 4]
+    );
+    assert_eq!(
+        reader.iter().copied().collect::<Vec<_>>(),
+        &[0, 1, 2, 3, 4, 5]
+    );
+
+    let before = reader.clone();
+    let copy = reader.make_mut() as *const LinkedList<i32>;
+    assert!(!reader.ptr_eq(&before));
+    drop(before);
+    assert_eq!(reader.make_mut() as *const LinkedList<i32>, copy);
+}
+
+#[test]
+fn test_cow_mutators() {
+    let original: CowList<i32> = (0..5).collect();
+
+    let mut copy = original.clone();
+    assert_eq!(copy.pop_front(), Some(0));
+    assert_eq!(copy.pop_back(), Some(4));
+    *copy.front_mut().unwrap() = 10;
+    *copy.back_mut().unwrap() *= 10;
+    for elem in copy.iter_mut() {
+        *elem += 1;
+    }
+    let mut cursor = copy.cursor_mut();
+    cursor.move_next();
+    cursor.splice_after(Some(7).into_iter().collect());
+    check_links(&copy);
+    assert_eq!(copy.iter().copied().collect::<Vec<_>>(), &[11, 7, 3, 31]);
+    assert_eq!(original, (0..5).collect());
+
+    let mut empty = CowList::<i32>::new();
+    let alias = empty.clone();
+    assert_eq!(empty.pop_front(), None);
+    assert!(empty.ptr_eq(&alias));
+
+    let mut cleared = original.clone();
+    cleared.clear();
+    assert!(cleared.is_empty());
+    assert_eq!(original.len(), 5);
+    assert_eq!(original.clone().into_inner(), (0..5).collect());
+}
+
+#[test]
+fn test_list_append_split_off() {
+    let mut m = generate_test();
+    let mut n = list_from(&[7, 8]);
+    m.append(&mut n);
+    assert!(n.is_empty());
+    check_links(&m);
+    assert_eq!(m, (0..9).collect());
+
+    for at in 0..=9 {
+        let mut head: LinkedList<i32> = (0..9).collect();
+        let tail = head.split_off(at);
+        check_links(&head);
+        check_links(&tail);
+        assert_eq!(head, (0..at as i32).collect());
+        assert_eq!(tail, (at as i32..9).collect());
+    }
+}
+
+#[test]
+fn test_journal_undo_redo() {
+    let mut journal = Journal::from(list_from(&[1, 2, 3]));
+    assert!(!journal.undo());
+
+    journal.push_back(4);
+    journal.push_front(0);
+    assert_eq!(journal.pop_back(), Some(&4));
+    assert_eq!(journal.pop_front(), Some(&0));
+    assert_eq!(journal.list(), &list_from(&[1, 2, 3]));
+
+    assert!(journal.undo());
+    assert_eq!(journal.list(), &list_from(&[0, 1, 2, 3]));
+    assert!(journal.undo());
+    assert_eq!(journal.list(), &list_from(&[0, 1, 2, 3, 4]));
+    assert!(journal.undo());
+    assert!(journal.undo());
+    assert_eq!(journal.list(), &list_from(&[1, 2, 3]));
+    assert!(!journal.undo());
+
+    assert!(journal.redo());
+    assert!(journal.redo());
+    assert_eq!(journal.list(), &list_from(&[0, 1, 2, 3, 4]));
+
+    journal.push_back(5);
+    assert!(!journal.can_redo());
+    assert_eq!(journal.list(), &list_from(&[0, 1, 2, 3, 4, 5]));
+    check_links(journal.list());
+
+    // Removed elements move into the log, so they don't need to be `Clone`.
+    #[derive(Debug, PartialEq)]
+    struct Token(u32);
+
+    let mut journal = Journal::new();
+    journal.push_back(Token(1));
+    journal.push_back(Token(2));
+    assert_eq!(journal.pop_front(), Some(&Token(1)));
+    let mut cursor = journal.cursor_mut();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(&Token(2)));
+    assert!(journal.is_empty());
+    journal.undo();
+    journal.undo();
+    assert!(journal.list().iter().eq(&[Token(1), Token(2)]));
+}
+
+#[test]
+fn test_journal_cursor_edits() {
+    let mut journal = Journal::from(list_from(&[1, 2, 3, 4, 5]));
+    let snapshots = [
+        list_from(&[1, 2, 3, 4, 5]),
+        list_from(&[1, 2, 9, 3, 4, 5]),
+        list_from(&[1, 2, 9, 4, 5]),
+        list_from(&[9, 4, 5]),
+        list_from(&[9, 4, 7, 8, 5]),
+        list_from(&[9, 4]),
+        list_from(&[9, 4, 6]),
+    ];
+
+    let mut cursor = journal.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&3));
+    cursor.insert_before(9);
+    assert_eq!(cursor.remove_current(), Some(&3));
+    assert_eq!(cursor.current(), Some(&4));
+    cursor.move_prev();
+    assert_eq!(cursor.split_before(), Some(&list_from(&[1, 2])));
+    cursor.move_next();
+    cursor.splice_after(list_from(&[7, 8]));
+    cursor.splice_after(LinkedList::new());
+    assert_eq!(cursor.split_after(), Some(&list_from(&[7, 8, 5])));
+    // Nothing after the last element, so nothing to undo either.
+    assert_eq!(cursor.split_after(), None);
+    cursor.move_next();
+    cursor.insert_before(6);
+
+    for snapshot in snapshots.iter().rev() {
+        assert_eq!(journal.list(), snapshot);
+        check_links(journal.list());
+        journal.undo();
+    }
+    assert!(!journal.can_undo());
+
+    for snapshot in snapshots.iter() {
+        assert_eq!(journal.list(), snapshot);
+        journal.redo();
+    }
+}
+
+#[test]
+fn test_journal_checkpoints() {
+    let mut journal = Journal::new();
+    journal.checkpoint("empty");
+    journal.push_back(1);
+    journal.push_back(2);
+    journal.checkpoint("two");
+    journal.push_back(3);
+
+    assert!(journal.restore("empty"));
+    assert!(journal.is_empty());
+    assert!(journal.restore("two"));
+    assert_eq!(journal.list(), &list_from(&[1, 2]));
+    assert!(!journal.restore("missing"));
+
+    journal.undo();
+    journal.push_front(0);
+    assert!(!journal.restore("two"));
+    assert!(journal.restore("empty"));
+    assert!(journal.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lru_recency() {
+    let mut cache = LruCache::new(3);
+    assert_eq!(cache.put("a", 1), None);
+    assert_eq!(cache.put("b", 2), None);
+    assert_eq!(cache.put("c", 3), None);
+    assert_eq!(
+        cache.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        &["c", "b", "a"]
+    );
+
+    assert_eq!(cache.get("a"), Some(&1));
+    assert_eq!(cache.peek("b"), Some(&2));
+    assert_eq!(cache.peek_lru(), Some((&"b", &2)));
+    assert_eq!(cache.put("d", 4), None);
+    assert!(!cache.contains("b"));
+    assert_eq!(
+        cache.iter().map(|(k, _)| *k).collect::<Vec<_>>(),
+        &["d", "a", "c"]
+    );
+    assert_eq!(
+        cache.iter().rev().map(|(k, _)| *k).collect::<Vec<_>>(),
+        &["c", "a", "d"]
+    );
+
+    assert_eq!(cache.put("c", 30), Some(3));
+    *cache.get_mut("a").unwrap() += 10;
+    assert_eq!(format!("{:?}", cache), r#"{"a": 11, "c": 30, "d": 4}"#);
+
+    assert_eq!(cache.pop("c"), Some(30));
+    assert_eq!(cache.pop("c"), None);
+    assert_eq!(cache.pop_lru(), Some(("d", 4)));
+    assert_eq!(cache.len(), 1);
+    cache.clear();
+    assert!(cache.is_empty());
+    assert_eq!(cache.get("a"), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lru_eviction_callback() {
+    use std::sync::{Arc, Mutex};
+
+    let evicted = Arc::new(Mutex::new(Vec::new()));
+    let mut cache = LruCache::new(4);
+    let sink = Arc::clone(&evicted);
+    cache.set_eviction_callback(move |key, value| sink.lock().unwrap().push((key, value)));
+
+    for i in 0..6 {
+        cache.put(i, i * 10);
+    }
+    assert_eq!(*evicted.lock().unwrap(), &[(0, 0), (1, 10)]);
+
+    cache.get(&2);
+    cache.resize(2);
+    assert_eq!(cache.cap(), 2);
+    assert_eq!(
+        *evicted.lock().unwrap(),
+        &[(0, 0), (1, 10), (3, 30), (4, 40)]
+    );
+    assert_eq!(cache.iter().map(|(k, _)| *k).collect::<Vec<_>>(), &[2, 5]);
+
+    assert_eq!(cache.pop_lru(), Some((5, 50)));
+    assert_eq!(evicted.lock().unwrap().len(), 4);
+
+    cache.resize(0);
+    assert!(cache.is_empty());
+    cache.put(9, 90);
+    assert!(cache.is_empty());
+    assert_eq!(evicted.lock().unwrap().last(), Some(&(9, 90)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lru_churn() {
+    let mut cache = LruCache::new(16);
+    let mut reference: Vec<(u32, u32)> = Vec::new();
+    let mut seed = 7u32;
+
+    for _ in 0..2_000 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        let key = (seed >> 16) % 40;
+
+        if let Some(pos) = reference.iter().position(|&(k, _)| k == key) {
+            let entry = reference.remove(pos);
+            reference.insert(0, entry);
+            assert_eq!(cache.get(&key), Some(&entry.1));
+        } else {
+            assert_eq!(cache.get(&key), None);
+            cache.put(key, seed);
+            reference.insert(0, (key, seed));
+            reference.truncate(16);
+        }
+    }
+
+    assert_eq!(
+        cache.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(),
+        reference
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_linked_hash_map_order() {
+    let mut map = LinkedHashMap::new();
+    assert_eq!(map.insert("one", 1), None);
+    assert_eq!(map.insert("two", 2), None);
+    assert_eq!(map.insert("three", 3), None);
+    assert_eq!(map.insert("one", 10), Some(1));
+    assert_eq!(
+        map.keys().copied().collect::<Vec<_>>(),
+        &["one", "two", "three"]
+    );
+    assert_eq!(map.values().rev().copied().collect::<Vec<_>>(), &[3, 2, 10]);
+
+    assert!(map.move_to_back("one"));
+    assert!(!map.move_to_back("four"));
+    assert!(map.move_to_front("three"));
+    assert_eq!(format!("{:?}", map), r#"{"three": 3, "two": 2, "one": 10}"#);
+
+    for (_, value) in map.iter_mut() {
+        *value *= 2;
+    }
+    assert_eq!(map.get("two"), Some(&4));
+    *map.get_mut("two").unwrap() += 1;
+    assert_eq!(map.remove("two"), Some(5));
+    assert_eq!(map.remove("two"), None);
+    assert!(!map.contains_key("two"));
+
+    assert_eq!(map.front(), Some((&"three", &6)));
+    assert_eq!(map.back(), Some((&"one", &20)));
+    assert_eq!(map.pop_front(), Some(("three", 6)));
+    assert_eq!(map.pop_back(), Some(("one", 20)));
+    assert!(map.is_empty());
+    assert_eq!(map.get("one"), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_linked_hash_map_entry() {
+    let mut counts: LinkedHashMap<char, usize> = LinkedHashMap::new();
+    for c in "mississippi".chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    assert_eq!(
+        counts.clone().into_iter().collect::<Vec<_>>(),
+        &[('m', 1), ('i', 4), ('s', 4), ('p', 2)]
+    );
+
+    counts.entry('m').and_modify(|n| *n += 10).or_insert(0);
+    counts.entry('z').and_modify(|n| *n += 10).or_insert(0);
+    assert_eq!(counts.get(&'m'), Some(&11));
+    assert_eq!(counts.back(), Some((&'z', &0)));
+
+    match counts.entry('s') {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.key(), &'s');
+            assert_eq!(entry.remove_entry(), ('s', 4));
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    assert_eq!(counts.entry('s').key(), &'s');
+    assert_eq!(counts.keys().collect::<String>(), "mipz");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_linked_hash_map_cursor() {
+    let mut map: LinkedHashMap<u32, u32> = (0..6).map(|i| (i, i * i)).collect();
+    let mut cursor = map.cursor_mut();
+    assert!(cursor.current().is_none());
+    assert_eq!(cursor.peek_prev(), Some((&5, &mut 25)));
+
+    cursor.move_next();
+    while let Some((&key, value)) = cursor.current() {
+        if key % 2 == 0 {
+            assert_eq!(cursor.remove_current().map(|(k, _)| k), Some(key));
+        } else {
+            *value += 1;
+            cursor.move_next();
+        }
+    }
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some((&5, &mut 26)));
+    assert_eq!(cursor.peek_next(), None);
+
+    assert_eq!(
+        map.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>(),
+        &[(1, 2), (3, 10), (5, 26)]
+    );
+    assert!(!map.contains_key(&2));
+    map.insert(2, 4);
+    assert_eq!(map.keys().copied().collect::<Vec<_>>(), &[1, 3, 5, 2]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_linked_hash_set() {
+    let mut set: LinkedHashSet<&str> = ["c", "a", "b", "a"].into_iter().collect();
+    assert_eq!(set.len(), 3);
+    assert!(!set.insert("c"));
+    assert!(set.insert("d"));
+    assert_eq!(
+        set.iter().copied().collect::<Vec<_>>(),
+        &["c", "a", "b", "d"]
+    );
+
+    assert!(set.move_to_back("c"));
+    assert!(set.move_to_front("d"));
+    assert_eq!(format!("{:?}", set), r#"{"d", "a", "b", "c"}"#);
+
+    assert!(set.contains("b"));
+    assert!(set.remove("b"));
+    assert!(!set.remove("b"));
+    assert_eq!(set.take("a"), Some("a"));
+    assert_eq!(set.front(), Some(&"d"));
+    assert_eq!(set.back(), Some(&"c"));
+    assert_eq!(set.pop_back(), Some("c"));
+    assert_eq!(set.clone().into_iter().rev().collect::<Vec<_>>(), &["d"]);
+    assert_eq!(set.pop_front(), Some("d"));
+    assert!(set.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_slru_scan_resistance() {
+    let trace = || [1, 2, 3, 1, 2, 3].into_iter().chain(100..110).chain(1..4);
+
+    let mut slru = SlruCache::with_protected(4, 3);
+    let stats = cache::replay(&mut slru, trace(), |&k| k * 10);
+    assert_eq!(
+        stats,
+        CacheStats {
+            hits: 6,
+            misses: 13,
+            evictions: 9
+        }
+    );
+    assert!(slru.contains(&1) && slru.contains(&2) && slru.contains(&3));
+    assert!(slru.contains(&109));
+    assert_eq!(slru.len(), 4);
+
+    let mut lru = LruCache::new(4);
+    let stats = cache::replay(&mut lru, trace(), |&k| k * 10);
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.misses, 16);
+    assert!(!lru.contains(&100));
+
+    assert_eq!(Cache::get(&mut slru, &2), Some(&20));
+    assert_eq!(slru.put(2, 21), Some(20));
+    assert_eq!(slru.get(&2), Some(&21));
+    assert_eq!(slru.stats().hits, 8);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_slru_tiny_protected() {
+    let mut slru = SlruCache::with_protected(2, 0);
+    slru.put("a", 1);
+    slru.put("b", 2);
+    assert_eq!(slru.get(&"a"), Some(&1));
+    assert_eq!(slru.get(&"a"), Some(&1));
+    slru.put("c", 3);
+    assert!(slru.contains(&"a"));
+    assert!(!slru.contains(&"b"));
+
+    let mut empty = SlruCache::new(0);
+    assert_eq!(empty.put(1, 1), None);
+    assert!(empty.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_lfu() {
+    let mut lfu = LfuCache::new(2);
+    assert_eq!(lfu.put("a", 1), None);
+    assert_eq!(lfu.get(&"a"), Some(&1));
+    assert_eq!(lfu.get(&"a"), Some(&1));
+    assert_eq!(lfu.frequency(&"a"), Some(3));
+
+    lfu.put("b", 2);
+    lfu.put("c", 3);
+    assert!(!lfu.contains(&"b"));
+    assert!(lfu.contains(&"a"));
+
+    lfu.put("d", 4);
+    assert!(!lfu.contains(&"c"));
+    assert_eq!(lfu.get(&"d"), Some(&4));
+    assert_eq!(lfu.put("d", 5), Some(4));
+    assert_eq!(lfu.frequency(&"d"), Some(3));
+
+    // Equally frequent, so the least recently used of the two goes.
+    lfu.put("e", 6);
+    assert!(!lfu.contains(&"a"));
+    assert!(lfu.contains(&"d"));
+    assert_eq!(lfu.len(), 2);
+    assert_eq!(
+        lfu.stats(),
+        CacheStats {
+            hits: 3,
+            misses: 0,
+            evictions: 3
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_arc_adapts() {
+    let mut arc = ArcCache::new(2);
+    arc.put(1, 1);
+    arc.put(2, 2);
+    arc.put(3, 3);
+    assert!(!arc.contains(&1));
+    assert_eq!(arc.get(&2), Some(&2));
+
+    arc.put(4, 4);
+    assert!(!arc.contains(&3));
+    assert_eq!(arc.target(), 0);
+
+    // 3 was evicted from the recent side too early.
+    arc.put(3, 30);
+    assert_eq!(arc.target(), 1);
+    assert!(arc.contains(&3) && arc.contains(&4));
+    assert!(!arc.contains(&2));
+
+    // And now 2 from the frequent side.
+    arc.put(2, 20);
+    assert_eq!(arc.target(), 0);
+    assert!(arc.contains(&2) && arc.contains(&3));
+    assert!(!arc.contains(&4));
+    assert_eq!(arc.get(&3), Some(&30));
+    assert_eq!(arc.len(), 2);
+    assert_eq!(arc.stats().evictions, 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_cache_replay() {
+    let mut trace: Vec<u32> = (0..4).chain(0..4).collect();
+    for round in 0..20 {
+        trace.extend(0..4);
+        trace.extend(round * 10 + 100..round * 10 + 106);
+    }
+
+    type Boxed = Box<dyn Cache<u32, u32>>;
+    let mut caches: Vec<(&str, Boxed)> = vec![
+        ("lru", Box::new(LruCache::new(8))),
+        ("slru", Box::new(SlruCache::new(8))),
+        ("lfu", Box::new(LfuCache::new(8))),
+        ("arc", Box::new(ArcCache::new(8))),
+    ];
+
+    let mut ratios = Vec::new();
+    for (name, cache) in &mut caches {
+        let stats = cache::replay(&mut **cache, trace.iter().copied(), |&k| k);
+        assert_eq!(stats.requests(), trace.len() as u64);
+        assert!(cache.len() <= cache.capacity());
+        ratios.push((*name, stats.hit_ratio()));
+    }
+
+    for &(name, ratio) in &ratios[1..] {
+        assert!(ratio > 0.3, "{} only hit {}", name, ratio);
+        assert!(ratio > ratios[0].1 * 5.0);
+    }
+}
+
+#[test]
+fn test_bounded_policies() {
+    let mut history = BoundedDeque::new(3, OverflowPolicy::EvictOldest);
+    for i in 0..3 {
+        assert_eq!(history.push(i), Ok(None));
+    }
+    assert!(history.is_full());
+    assert_eq!(history.push(3), Ok(Some(0)));
+    assert_eq!(history.push(4), Ok(Some(1)));
+    assert_eq!(format!("{:?}", history), "[2, 3, 4]");
+
+    history.set_policy(OverflowPolicy::EvictNewest);
+    assert_eq!(history.push(5), Ok(Some(4)));
+    assert_eq!(history.iter().copied().collect::<Vec<_>>(), &[2, 3, 5]);
+
+    history.set_policy(OverflowPolicy::Reject);
+    assert_eq!(history.push(6), Err(6));
+    history.set_policy(OverflowPolicy::Block);
+    assert_eq!(history.push(6), Err(6));
+    assert_eq!(history.pop_front(), Some(2));
+    assert_eq!(history.push(6), Ok(None));
+    assert_eq!(history.front(), Some(&3));
+    assert_eq!(history.back(), Some(&6));
+
+    let mut none = BoundedDeque::new(0, OverflowPolicy::EvictNewest);
+    assert_eq!(none.push(1), Ok(Some(1)));
+    none.set_policy(OverflowPolicy::EvictOldest);
+    assert_eq!(none.push(2), Ok(Some(2)));
+    assert!(none.is_empty());
+}
+
+#[test]
+fn test_bounded_set_capacity() {
+    let mut deque = BoundedDeque::new(6, OverflowPolicy::Reject);
+    for i in 0..6 {
+        deque.push(i).unwrap();
+    }
+
+    let evicted = deque.set_capacity(4);
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[0, 1]);
+    assert_eq!(deque.cap(), 4);
+    assert!(deque.set_capacity(10).is_empty());
+    deque.set_capacity(4);
+
+    deque.set_policy(OverflowPolicy::EvictNewest);
+    let evicted = deque.set_capacity(1);
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[3, 4, 5]);
+    assert_eq!(deque.into_list().into_iter().collect::<Vec<_>>(), &[2]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_sync_bounded_block() {
+    use std::thread;
+
+    let deque = SyncBoundedDeque::new(2, OverflowPolicy::Block);
+    let producer = {
+        let deque = deque.clone();
+        thread::spawn(move || {
+            for i in 0..100 {
+                assert_eq!(deque.push(i), Ok(None));
+            }
+        })
+    };
+
+    let mut seen = Vec::new();
+    while seen.len() < 100 {
+        assert!(deque.len() <= 2);
+        seen.push(deque.take());
+    }
+    producer.join().unwrap();
+    assert_eq!(seen, (0..100).collect::<Vec<_>>());
+
+    deque.try_push(1).unwrap();
+    deque.try_push(2).unwrap();
+    assert_eq!(deque.try_push(3), Err(3));
+    let blocked = {
+        let deque = deque.clone();
+        thread::spawn(move || deque.push(3))
+    };
+    assert_eq!(deque.set_capacity(3).len(), 0);
+    assert_eq!(blocked.join().unwrap(), Ok(None));
+
+    deque.set_policy(OverflowPolicy::EvictOldest);
+    assert_eq!(deque.push(4), Ok(Some(1)));
+    assert_eq!(deque.pop_back(), Some(4));
+    assert_eq!(deque.drain().into_iter().collect::<Vec<_>>(), &[2, 3]);
+    assert!(deque.is_empty());
+
+    // With no capacity there's never room, so blocking would be forever.
+    let empty = SyncBoundedDeque::new(0, OverflowPolicy::Block);
+    assert_eq!(empty.push(1), Err(1));
+
+    deque.set_policy(OverflowPolicy::Block);
+    deque.set_capacity(1);
+    deque.push(1).unwrap();
+    let blocked = {
+        let deque = deque.clone();
+        thread::spawn(move || deque.push(2))
+    };
+    // Whether or not it's waiting yet, it gets its element back.
+    let evicted = deque.set_capacity(0);
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[1]);
+    assert_eq!(blocked.join().unwrap(), Err(2));
+    assert!(deque.is_empty());
+}
+
+#[test]
+fn test_weighted_push_pop() {
+    let mut buf = WeightedDeque::new(10, WeightPolicy::EvictFront, ByteLen);
+    assert!(buf.push_back("abcd").unwrap().is_empty());
+    assert!(buf.push_back("efg").unwrap().is_empty());
+    assert_eq!(buf.weight(), 7);
+
+    let evicted = buf.push_back("hijkl").unwrap();
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &["abcd"]);
+    assert_eq!(buf.weight(), 8);
+    assert_eq!(buf.remaining(), 2);
+
+    assert_eq!(buf.push_back("this is too long"), Err("this is too long"));
+    assert_eq!(buf.weight(), 8);
+
+    buf.set_policy(WeightPolicy::EvictBack);
+    let evicted = buf.push_front("mnopqr").unwrap();
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &["hijkl"]);
+    assert_eq!(format!("{:?}", buf), r#"["mnopqr", "efg"]"#);
+
+    buf.set_policy(WeightPolicy::Reject);
+    assert_eq!(buf.push_back("st"), Err("st"));
+    assert!(buf.push_back("s").unwrap().is_empty());
+    assert_eq!(buf.remaining(), 0);
+    assert!(buf.push_front("").unwrap().is_empty());
+
+    assert_eq!(buf.pop_front(), Some(""));
+    assert_eq!(buf.pop_front(), Some("mnopqr"));
+    assert_eq!(buf.weight(), 4);
+    assert_eq!(buf.pop_back(), Some("s"));
+    assert_eq!(buf.pop_back(), Some("efg"));
+    assert_eq!(buf.pop_back(), None);
+    assert_eq!(buf.weight(), 0);
+}
+
+#[test]
+fn test_weighted_split_append() {
+    let weigh = |n: &u32| *n as usize;
+    let mut deque = WeightedDeque::new(100, WeightPolicy::EvictFront, weigh);
+    for i in 1..=10 {
+        deque.push_back(i).unwrap();
+    }
+    assert_eq!(deque.weight(), 55);
+
+    let mut tail = deque.split_off(8);
+    assert_eq!(tail.weight(), 19);
+    assert_eq!(deque.weight(), 36);
+    let mut mid = deque.split_off(2);
+    assert_eq!(mid.weight(), 33);
+    assert_eq!(deque.weight(), 3);
+    assert_eq!(deque.split_off(2).weight(), 0);
+
+    deque.append(&mut tail).unwrap();
+    assert_eq!(deque.weight(), 22);
+    assert!(tail.is_empty());
+    assert_eq!(tail.weight(), 0);
+
+    mid.set_budget(40);
+    let evicted = mid.append(&mut deque).unwrap();
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[3, 4, 5, 6]);
+    assert_eq!(mid.weight(), 37);
+    assert_eq!(
+        mid.iter().copied().collect::<Vec<_>>(),
+        &[7, 8, 1, 2, 9, 10]
+    );
+
+    let mut heavy = WeightedDeque::new(100, WeightPolicy::Reject, weigh);
+    heavy.push_back(50).unwrap();
+    assert_eq!(mid.append(&mut heavy), Err(OverBudget));
+    assert_eq!(heavy.weight(), 50);
+    assert_eq!(mid.len(), 6);
+
+    mid.set_policy(WeightPolicy::EvictBack);
+    let evicted = mid.set_budget(20);
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[9, 10]);
+    assert_eq!(mid.weight(), 18);
+    assert_eq!(
+        mid.into_list().into_iter().collect::<Vec<_>>(),
+        &[7, 8, 1, 2]
+    );
+}
+
+#[test]
+fn test_weighted_splice_and_stored_weights() {
+    use std::cell::Cell;
+
+    // Weighs heavier every call, so anything that weighed twice would be off.
+    let calls = Cell::new(0);
+    let weigh = |n: &u32| {
+        calls.set(calls.get() + 1);
+        *n as usize + calls.get()
+    };
+    let mut deque = WeightedDeque::new(100, WeightPolicy::EvictFront, &weigh);
+    for i in [1, 2, 3] {
+        deque.push_back(i).unwrap();
+    }
+    assert_eq!(deque.weight(), 2 + 4 + 6);
+    assert_eq!(deque.pop_front(), Some(1));
+    assert_eq!(deque.weight(), 10);
+    let tail = deque.split_off(1);
+    assert_eq!((deque.weight(), tail.weight()), (4, 6));
+    assert_eq!(calls.get(), 3);
+
+    let weigh = |n: &u32| *n as usize;
+    let mut deque = WeightedDeque::new(21, WeightPolicy::EvictFront, weigh);
+    for i in [1, 2, 3, 4] {
+        deque.push_back(i).unwrap();
+    }
+    let mut run = WeightedDeque::new(20, WeightPolicy::Reject, weigh);
+    run.push_back(5).unwrap();
+    run.push_back(6).unwrap();
+
+    assert!(deque.splice(2, &mut run).unwrap().is_empty());
+    assert_eq!(
+        deque.iter().copied().collect::<Vec<_>>(),
+        &[1, 2, 5, 6, 3, 4]
+    );
+    assert_eq!(deque.weight(), 21);
+    assert!(run.is_empty());
+    assert_eq!(run.weight(), 0);
+
+    // Makes room from the front first, which shifts the splice point.
+    run.push_back(7).unwrap();
+    let evicted = deque.splice(3, &mut run).unwrap();
+    assert_eq!(evicted.into_iter().collect::<Vec<_>>(), &[1, 2, 5]);
+    assert_eq!(deque.iter().copied().collect::<Vec<_>>(), &[7, 6, 3, 4]);
+    assert_eq!(deque.weight(), 20);
+
+    deque.set_policy(WeightPolicy::Reject);
+    run.push_back(7).unwrap();
+    assert_eq!(deque.splice(0, &mut run), Err(OverBudget));
+    assert_eq!(run.weight(), 7);
+    assert_eq!(deque.len(), 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_expiring() {
+    use std::time::Duration;
+
+    let clock = ManualClock::new();
+    let mut tokens = ExpiringDeque::with_clock(Duration::from_secs(10), clock.clone());
+    assert_eq!(tokens.next_deadline(), None);
+
+    let first = tokens.push_back("a");
+    assert_eq!(first, Some(clock.now() + Duration::from_secs(10)));
+    clock.advance(Duration::from_secs(4));
+    tokens.push_back("b");
+    clock.advance(Duration::from_secs(4));
+    tokens.push_back("c");
+    assert_eq!(tokens.next_deadline(), first);
+    assert_eq!(tokens.pop_expired().next(), None);
+
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(tokens.len(), 3);
+    assert!(!tokens.contains(&"a"));
+    assert!(tokens.contains(&"b"));
+    assert_eq!(tokens.front(), Some(&"b"));
+    assert_eq!(tokens.back(), Some(&"c"));
+    assert_eq!(format!("{:?}", tokens), r#"["b", "c"]"#);
+    assert_eq!(tokens.iter().rev().collect::<Vec<_>>(), &[&"c", &"b"]);
+    assert_eq!(tokens.find(|t| t.starts_with('c')), Some(&"c"));
+
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(tokens.pop_expired().collect::<Vec<_>>(), &["a", "b"]);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(
+        tokens.next_deadline(),
+        first.map(|first| first + Duration::from_secs(8))
+    );
+
+    tokens.push_back("d");
+    clock.advance(Duration::from_secs(7));
+    assert_eq!(tokens.pop_front(), Some("d"));
+    assert!(tokens.is_empty());
+
+    tokens.push_back("e");
+    tokens.push_back("f");
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(tokens.front(), None);
+    assert_eq!(tokens.back(), None);
+    assert_eq!(tokens.iter().len(), 0);
+    assert_eq!(tokens.pop_back(), None);
+    assert!(tokens.is_empty());
+
+    tokens.push_back("g");
+    tokens.push_back("h");
+    assert_eq!(tokens.pop_back(), Some("h"));
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(tokens.purge(), 1);
+
+    // A TTL past what `Instant` can hold means never expiring.
+    let mut forever = ExpiringDeque::with_clock(Duration::MAX, clock.clone());
+    assert_eq!(forever.push_back("x"), None);
+    assert_eq!(forever.push_back("y"), None);
+    assert_eq!(forever.next_deadline(), None);
+    clock.advance(Duration::from_secs(1_000_000));
+    assert_eq!(forever.purge(), 0);
+    assert_eq!(forever.iter().collect::<Vec<_>>(), &[&"x", &"y"]);
+    assert_eq!(forever.back(), Some(&"y"));
+    assert_eq!(forever.pop_back(), Some("y"));
+    assert_eq!(forever.pop_front(), Some("x"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_timer_wheel_basics() {
+    let mut wheel = TimerWheel::new();
+    let a = wheel.insert("a", 5);
+    let b = wheel.insert("b", 70);
+    let c = wheel.insert("c", 5);
+    let d = wheel.insert("d", 5000);
+    assert_eq!(wheel.len(), 4);
+    assert_eq!(wheel.deadline(b), Some(70));
+    assert_eq!(wheel.next_deadline(), Some(5));
+
+    assert!(wheel.advance(4).is_empty());
+    assert_eq!(wheel.cancel(c), Some("c"));
+    assert_eq!(wheel.cancel(c), None);
+    let fired = wheel.advance(5);
+    assert_eq!(fired.into_iter().collect::<Vec<_>>(), &["a"]);
+    assert!(!wheel.contains(a));
+    assert!(!wheel.reset(a, 1));
+
+    assert!(wheel.reset(d, 10));
+    assert_eq!(wheel.deadline(d), Some(15));
+    *wheel.get_mut(d).unwrap() = "D";
+    let fired = wheel.advance(100);
+    assert_eq!(fired.into_iter().collect::<Vec<_>>(), &["D", "b"]);
+    assert!(wheel.is_empty());
+    assert_eq!(wheel.elapsed(), 100);
+    assert_eq!(wheel.next_deadline(), None);
+
+    let now = wheel.insert("now", 0);
+    assert_eq!(wheel.get(now), Some(&"now"));
+    let fired = wheel.advance(50);
+    assert_eq!(fired.into_iter().collect::<Vec<_>>(), &["now"]);
+    assert_eq!(wheel.elapsed(), 100);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_timer_wheel_cascade() {
+    let mut wheel = TimerWheel::starting_at(1000);
+    let mut expected = Vec::new();
+    let mut handles = Vec::new();
+
+    let mut seed = 7u64;
+    for i in 0..2000u64 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let delay = match i % 4 {
+            0 => seed >> 58,
+            1 => seed >> 50,
+            2 => seed >> 40,
+            _ => seed >> 30,
+        };
+        handles.push(wheel.insert(i, delay));
+        expected.push((1000 + delay, i));
+    }
+
+    for (i, handle) in handles.iter().enumerate().step_by(3) {
+        assert_eq!(wheel.cancel(*handle), Some(i as u64));
+    }
+    expected.retain(|&(_, i)| i % 3 != 0);
+    expected.sort();
+
+    let mut fired = Vec::new();
+    let mut now = 1000;
+    while !wheel.is_empty() {
+        now += 1 + now / 3;
+        for i in wheel.advance(now) {
+            let deadline = expected.iter().find(|&&(_, j)| j == i).unwrap().0;
+            assert!(deadline <= now);
+            fired.push((deadline, i));
+        }
+    }
+
+    // Within one call everything comes out in deadline order, ties in
+    // insertion order.
+    assert_eq!(fired, expected);
+}
+
+#[test]
+fn test_monotonic_deque() {
+    let mut deque = MonotonicDeque::new();
+    assert_eq!(deque.best(), None);
+    assert_eq!(deque.push(5), 0);
+    assert_eq!(deque.push(3), 1);
+    assert_eq!(deque.push(4), 2);
+    assert_eq!(deque.push(3), 3);
+    assert_eq!(format!("{:?}", deque), "[(3, 3)]");
+    assert_eq!(deque.best_index(), Some(3));
+    deque.push(8);
+    deque.expire(3);
+    assert_eq!(deque.best(), Some(&3));
+    deque.expire(4);
+    assert_eq!(deque.best(), Some(&8));
+    deque.expire(5);
+    assert!(deque.is_empty());
+
+    let mut longest =
+        MonotonicDeque::with_comparator(Reversed(|a: &&str, b: &&str| a.len().cmp(&b.len())));
+    longest.push("ab");
+    longest.push("abcd");
+    longest.push("abc");
+    assert_eq!(longest.best(), Some(&"abcd"));
+    longest.expire(2);
+    assert_eq!(longest.best(), Some(&"abc"));
+
+    // Any deque will do, and whatever it held is dropped.
+    let mut backing = std::collections::VecDeque::new();
+    backing.push_back((7, 7));
+    let mut deque = MonotonicDeque::with_deque(Natural, backing);
+    assert!(deque.is_empty());
+    deque.push(2);
+    deque.push(1);
+    deque.push(3);
+    assert_eq!(format!("{:?}", deque), "[(1, 1), (2, 3)]");
+}
+
+#[test]
+fn test_sliding_window() {
+    let values = [4, 2, 12, 11, -5, 7, 7, 3, 0, 9, 1, 1, 15];
+
+    for width in 1..=5 {
+        let mut window = SlidingWindow::new();
+        for (i, &value) in values.iter().enumerate() {
+            assert_eq!(window.push(value), i);
+            window.expire((i + 1).saturating_sub(width));
+            assert_eq!(window.len(), width.min(i + 1));
+
+            let slice = &values[window.start()..=i];
+            assert_eq!(window.min(), slice.iter().min());
+            assert_eq!(window.max(), slice.iter().max());
+        }
+    }
+
+    let mut window = SlidingWindow::with_comparator(|a: &i32, b: &i32| a.abs().cmp(&b.abs()));
+    window.push(-3);
+    window.push(2);
+    window.push(-1);
+    assert_eq!(window.min(), Some(&-1));
+    assert_eq!(window.max(), Some(&-3));
+    window.expire(100);
+    assert!(window.is_empty());
+    assert_eq!(window.min(), None);
+    assert_eq!(window.start(), 3);
+
+    let mut window = SlidingWindow::with_deques(
+        Natural,
+        std::collections::VecDeque::new(),
+        std::collections::VecDeque::new(),
+    );
+    for (i, &value) in values.iter().enumerate() {
+        window.push(value);
+        window.expire((i + 1).saturating_sub(4));
+        let slice = &values[window.start()..=i];
+        assert_eq!(window.min(), slice.iter().min());
+        assert_eq!(window.max(), slice.iter().max());
+    }
+}
+
+#[test]
+fn test_aggregate_window() {
+    fn gcd(a: &u64, b: &u64) -> u64 {
+        if *b == 0 {
+            *a
+        } else {
+            gcd(b, &(a % b))
+        }
+    }
+
+    let values = [12u64, 18, 30, 7, 14, 28, 42, 6, 9];
+    let mut sums = AggregateWindow::new(|a: &u64, b: &u64| a + b);
+    let mut gcds = AggregateWindow::new(gcd);
+    for (i, &value) in values.iter().enumerate() {
+        sums.push(value);
+        gcds.push(value);
+        sums.expire((i + 1).saturating_sub(3));
+        gcds.expire((i + 1).saturating_sub(3));
+
+        let slice = &values[sums.start()..=i];
+        assert_eq!(sums.aggregate(), Some(slice.iter().sum()));
+        assert_eq!(
+            gcds.aggregate(),
+            slice.iter().copied().reduce(|a, b| gcd(&a, &b))
+        );
+    }
+
+    // Not commutative, so the order has to survive moving between stacks.
+    let mut text = AggregateWindow::new(|a: &String, b: &String| format!("{}{}", a, b));
+    assert_eq!(text.aggregate(), None);
+    for word in ["a", "b", "c", "d"] {
+        text.push(word.to_string());
+    }
+    assert_eq!(text.pop_front().as_deref(), Some("a"));
+    text.push("e".to_string());
+    assert_eq!(text.aggregate().as_deref(), Some("bcde"));
+    text.expire(3);
+    assert_eq!(text.aggregate().as_deref(), Some("de"));
+    assert_eq!(text.len(), 2);
+    text.clear();
+    assert!(text.is_empty());
+    assert_eq!(text.aggregate(), None);
+
+    let mut maxes = AggregateWindow::with_deques(
+        |a: &u64, b: &u64| *a.max(b),
+        std::collections::VecDeque::new(),
+        std::collections::LinkedList::new(),
+    );
+    for (i, &value) in values.iter().enumerate() {
+        maxes.push(value);
+        maxes.expire((i + 1).saturating_sub(3));
+        let slice = &values[maxes.start()..=i];
+        assert_eq!(maxes.aggregate(), slice.iter().max().copied());
+    }
+}
+
+#[test]
+fn test_sorted_insert_stable() {
+    #[derive(Debug, Clone, Copy)]
+    struct Job(u32, char);
+
+    impl PartialEq for Job {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Job {}
+    impl PartialOrd for Job {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Job {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    let mut jobs = SortedList::new();
+    assert_eq!(jobs.insert(Job(2, 'a')), 0);
+    assert_eq!(jobs.insert(Job(1, 'b')), 0);
+    assert_eq!(jobs.insert(Job(2, 'c')), 2);
+    assert_eq!(jobs.insert(Job(3, 'd')), 3);
+    assert_eq!(jobs.insert(Job(2, 'e')), 3);
+    let tags = |jobs: &SortedList<Job>| jobs.iter().map(|job| job.1).collect::<String>();
+    assert_eq!(tags(&jobs), "baced");
+
+    jobs.extend([Job(2, 'f'), Job(0, 'g'), Job(2, 'h'), Job(4, 'i')]);
+    assert_eq!(tags(&jobs), "gbacefhdi");
+
+    assert_eq!(jobs.remove(&Job(2, '?')).map(|job| job.1), Some('a'));
+    assert_eq!(jobs.remove(&Job(5, '?')), None);
+    assert_eq!(jobs.rank(&Job(2, '?')), 2);
+    assert_eq!(jobs.rank(&Job(9, '?')), 8);
+    assert_eq!(jobs.first().map(|job| job.1), Some('g'));
+    assert_eq!(jobs.last().map(|job| job.1), Some('i'));
+}
+
+#[test]
+fn test_sorted_range() {
+    let mut list: SortedList<String> = ["pear", "apple", "fig", "kiwi", "date"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    list.insert("banana".to_string());
+    assert_eq!(
+        format!("{:?}", list),
+        r#"["apple", "banana", "date", "fig", "kiwi", "pear"]"#
+    );
+
+    fn range(list: &SortedList<String>, r: (Bound<&str>, Bound<&str>)) -> Vec<String> {
+        list.range::<str, _>(r).cloned().collect()
+    }
+    use std::ops::Bound::{self, *};
+    assert_eq!(
+        range(&list, (Included("b"), Excluded("fig"))),
+        &["banana", "date"]
+    );
+    assert_eq!(
+        range(&list, (Excluded("fig"), Unbounded)),
+        &["kiwi", "pear"]
+    );
+    assert_eq!(range(&list, (Unbounded, Included("fig"))).len(), 4);
+    assert!(range(&list, (Included("q"), Unbounded)).is_empty());
+    assert!(range(&list, (Included("k"), Excluded("c"))).is_empty());
+
+    let mut numbers: SortedList<i32> = (0..10).rev().collect();
+    assert_eq!(
+        numbers.range(3..6).rev().copied().collect::<Vec<_>>(),
+        &[5, 4, 3]
+    );
+    assert_eq!(numbers.range(..=2).len(), 3);
+    assert!(list.contains("kiwi"));
+    assert!(!list.contains("lime"));
+    assert_eq!(list.remove("kiwi").as_deref(), Some("kiwi"));
+    assert!(!list.contains("kiwi"));
+
+    numbers.extend([5, -1, 20]);
+    assert_eq!(numbers.pop_first(), Some(-1));
+    assert_eq!(numbers.pop_last(), Some(20));
+    assert_eq!(
+        numbers.into_iter().collect::<Vec<_>>(),
+        &[0, 1, 2, 3, 4, 5, 5, 6, 7, 8, 9]
+    );
+}
+
+fn check_indexed(list: &IndexedList<u32>, expected: &[u32]) {
+    assert_eq!(list.len(), expected.len());
+    assert_eq!(list.iter().copied().collect::<Vec<_>>(), expected);
+    assert_eq!(list.iter().rev().copied().collect::<Vec<_>>(), {
+        let mut reversed = expected.to_vec();
+        reversed.reverse();
+        reversed
+    });
+    for (i, elem) in expected.iter().enumerate() {
+        assert_eq!(list.get(i), Some(elem));
+    }
+    assert_eq!(list.get(expected.len()), None);
+}
+
+#[test]
+fn test_indexed_ends() {
+    let mut list = IndexedList::new();
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+
+    let mut expected = std::collections::VecDeque::new();
+    for i in 0..300 {
+        if i % 3 == 0 {
+            list.push_front(i);
+            expected.push_front(i);
+        } else {
+            list.push_back(i);
+            expected.push_back(i);
+        }
+        if i % 7 == 0 {
+            assert_eq!(list.pop_front(), expected.pop_front());
+        }
+        if i % 11 == 0 {
+            assert_eq!(list.pop_back(), expected.pop_back());
+        }
+    }
+    check_indexed(&list, expected.make_contiguous());
+
+    *list.front_mut().unwrap() += 1000;
+    *list.back_mut().unwrap() += 1000;
+    assert_eq!(list.front(), Some(&(expected[0] + 1000)));
+    assert_eq!(list.back(), Some(&(expected[expected.len() - 1] + 1000)));
+
+    let drained: Vec<_> = list.into_iter().rev().collect();
+    assert_eq!(drained.len(), expected.len());
+}
+
+#[test]
+fn test_indexed_random_ops() {
+    let mut seed = 0x2545f4914f6cdd1du64;
+    let mut rand = move |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+
+    let mut list = IndexedList::new();
+    let mut expected = Vec::new();
+    for round in 0..3000u32 {
+        match rand(6) {
+            0..=2 => {
+                let at = rand(expected.len() + 1);
+                list.insert(at, round);
+                expected.insert(at, round);
+            }
+            3 if !expected.is_empty() => {
+                let at = rand(expected.len());
+                assert_eq!(list.remove(at), Some(expected.remove(at)));
+            }
+            4 => {
+                list.push_front(round);
+                expected.insert(0, round);
+            }
+            _ => {
+                assert_eq!(list.pop_back(), expected.pop());
+            }
+        }
+
+        if round % 500 == 0 {
+            check_indexed(&list, &expected);
+        }
+    }
+    check_indexed(&list, &expected);
+    assert_eq!(list.remove(expected.len()), None);
+
+    for _ in 0..20 {
+        let at = rand(expected.len() + 1);
+        let mut tail = list.split_off(at);
+        let expected_tail = expected.split_off(at);
+        check_indexed(&list, &expected);
+        check_indexed(&tail, &expected_tail);
+
+        tail.push_front(7);
+        list.push_back(8);
+        tail.insert(tail.len() / 2, 9);
+        expected.push(8);
+        let mut expected_tail = expected_tail;
+        expected_tail.insert(0, 7);
+        expected_tail.insert(expected_tail.len() / 2, 9);
+
+        list.append(&mut tail);
+        expected.extend(expected_tail);
+        assert!(tail.is_empty());
+        check_indexed(&list, &expected);
+    }
+
+    let mut empty = IndexedList::new();
+    empty.append(&mut list);
+    check_indexed(&empty, &expected);
+    assert_eq!(empty.split_off(0).len(), expected.len());
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_indexed_cursor() {
+    let mut list: IndexedList<u32> = (0..100).collect();
+    let mut cursor = list.cursor_mut();
+    cursor.seek(40);
+    assert_eq!(cursor.current(), Some(&mut 40));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(39));
+    assert_eq!(cursor.peek_next(), Some(&mut 40));
+    assert_eq!(cursor.remove_current(), Some(39));
+    assert_eq!(cursor.index(), Some(39));
+    assert_eq!(cursor.current(), Some(&mut 40));
+
+    cursor.insert_before(1000);
+    cursor.insert_after(1001);
+    assert_eq!(cursor.index(), Some(40));
+    assert_eq!(cursor.peek_prev(), Some(&mut 1000));
+    assert_eq!(cursor.peek_next(), Some(&mut 1001));
+
+    cursor.seek(102);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.current(), Some(&mut 99));
+    assert_eq!(cursor.remove_current(), Some(99));
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(2000);
+    cursor.insert_after(3000);
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 3000));
+    assert_eq!(cursor.as_list().len(), 102);
+
+    let mut expected: Vec<u32> = (0..99).filter(|&i| i != 39).collect();
+    expected.insert(39, 1000);
+    expected.insert(41, 1001);
+    expected.insert(0, 3000);
+    expected.push(2000);
+    check_indexed(&list, &expected);
+
+    for elem in list.iter_mut() {
+        *elem += 1;
+    }
+    assert_eq!(list.get(0), Some(&3001));
+    assert_eq!(format!("{:?}", list.split_off(100)), "[99, 2001]");
+    assert_eq!(list.clone(), list);
+}
+
+fn check_tree(list: &TreeList<u32>, expected: &[u32]) {
+    assert_eq!(list.len(), expected.len());
+    assert!(list.iter().eq(expected));
+    assert!(list.iter().rev().eq(expected.iter().rev()));
+    for (i, elem) in expected.iter().enumerate() {
+        assert_eq!(list.get(i), Some(elem));
+    }
+    assert_eq!(list.get(expected.len()), None);
+}
+
+#[test]
+fn test_tree_list_random_ops() {
+    let mut seed = 0x9e3779b97f4a7c15u64;
+    let mut rand = move |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+
+    let mut list = TreeList::new();
+    let mut expected = Vec::new();
+    for round in 0..3000u32 {
+        match rand(7) {
+            0..=2 => {
+                let at = rand(expected.len() + 1);
+                list.insert(at, round);
+                expected.insert(at, round);
+            }
+            3 if !expected.is_empty() => {
+                let at = rand(expected.len());
+                assert_eq!(list.remove(at), Some(expected.remove(at)));
+            }
+            4 => {
+                list.push_front(round);
+                expected.insert(0, round);
+            }
+            5 => {
+                assert_eq!(
+                    list.pop_front(),
+                    (!expected.is_empty()).then(|| expected.remove(0))
+                );
+            }
+            _ => {
+                assert_eq!(list.pop_back(), expected.pop());
+            }
+        }
+
+        if round % 500 == 0 {
+            check_tree(&list, &expected);
+        }
+    }
+    check_tree(&list, &expected);
+    assert_eq!(list.remove(expected.len()), None);
+
+    for _ in 0..20 {
+        let at = rand(expected.len() + 1);
+        let mut tail = list.split_off(at);
+        let expected_tail = expected.split_off(at);
+        check_tree(&list, &expected);
+        check_tree(&tail, &expected_tail);
+
+        list.append(&mut tail);
+        expected.extend(expected_tail);
+        assert!(tail.is_empty());
+    }
+    check_tree(&list, &expected);
+
+    let (start, end) = (expected.len() / 3, expected.len() / 2);
+    assert!(list.range(start..end).eq(&expected[start..end]));
+    assert!(list
+        .range(start..=end)
+        .rev()
+        .eq(expected[start..=end].iter().rev()));
+    assert_eq!(list.range(end..end).next(), None);
+    assert_eq!(list.range(..).len(), expected.len());
+
+    let mut iter = list.iter_mut();
+    let (first, last) = (iter.next().unwrap(), iter.next_back().unwrap());
+    *first += 1;
+    *last += 1;
+    assert_eq!(iter.len(), expected.len() - 2);
+    for elem in iter {
+        *elem += 1;
+    }
+    for elem in expected.iter_mut() {
+        *elem += 1;
+    }
+    check_tree(&list, &expected);
+    assert!(list.into_iter().rev().eq(expected.into_iter().rev()));
+}
+
+#[test]
+fn test_tree_list_ends() {
+    let mut list = TreeList::new();
+    assert_eq!(list.front(), None);
+    assert_eq!(list.pop_back(), None);
+    list.push_back(2);
+    list.push_front(1);
+    list.push_back(3);
+    *list.front_mut().unwrap() *= 10;
+    *list.back_mut().unwrap() *= 10;
+    assert_eq!(format!("{:?}", list), "[10, 2, 30]");
+    assert_eq!(list.clone(), list);
+
+    let other: TreeList<u32> = [10, 2, 30].into_iter().collect();
+    let shorter: TreeList<u32> = [10, 2].into_iter().collect();
+    let bigger: TreeList<u32> = [10, 3].into_iter().collect();
+    assert!(shorter < other && other < bigger);
+    assert_eq!(other.cmp(&list), std::cmp::Ordering::Equal);
+    let mut hashes = std::collections::HashSet::new();
+    hashes.insert(list);
+    assert!(hashes.contains(&other));
+
+    // Every element borrowed at once, taken alternately from both ends.
+    let mut list: TreeList<u32> = (0..50).collect();
+    let mut iter = list.iter_mut();
+    let mut elems = Vec::new();
+    while let Some(elem) = iter.next() {
+        elems.push(elem);
+        elems.extend(iter.next_back());
+    }
+    for elem in elems {
+        *elem += 1;
+    }
+    assert!(list.iter().copied().eq(1..51));
+
+    let mut long: TreeList<u32> = (0..100_000).collect();
+    assert_eq!(long.get(54_321), Some(&54_321));
+    long.clear();
+    assert!(long.is_empty());
+}
+
+#[test]
+fn test_tree_list_cursor() {
+    let mut list: TreeList<u32> = (1..=6).collect();
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 6));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.index(), Some(1));
+
+    let front = cursor.split_before();
+    assert_eq!(cursor.index(), Some(0));
+    assert!(front.iter().eq(&[1]));
+    cursor.move_next();
+    let back = cursor.split_after();
+    assert!(back.iter().eq(&[4, 5, 6]));
+    assert!(cursor.as_list().iter().eq(&[2, 3]));
+
+    cursor.splice_before(back);
+    assert_eq!(cursor.index(), Some(4));
+    assert_eq!(cursor.current(), Some(&mut 3));
+    cursor.splice_after(front);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert!(cursor.as_list().iter().eq(&[2, 4, 5, 6, 3, 1]));
+
+    cursor.insert_before(7);
+    cursor.insert_after(8);
+    assert_eq!(cursor.index(), Some(5));
+    assert_eq!(cursor.remove_current(), Some(3));
+    assert_eq!(cursor.current(), Some(&mut 8));
+    cursor.seek(6);
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.index(), None);
+
+    // At the ghost both ends are fair game, same as `LinkedList`.
+    cursor.splice_before((10..12).collect());
+    cursor.splice_after((20..22).collect());
+    cursor.insert_before(30);
+    cursor.insert_after(31);
+    assert!(list.iter().eq(&[31, 20, 21, 2, 4, 5, 6, 7, 8, 10, 11, 30]));
+
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.split_after().len(), 12);
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+    assert!(cursor.split_before().is_empty());
+}
+
+#[test]
+fn test_tree_list_sort() {
+    let mut list: TreeList<u32> = [5, 3, 9, 1, 3, 7, 0, 8].into_iter().collect();
+    list.sort();
+    check_tree(&list, &[0, 1, 3, 3, 5, 7, 8, 9]);
+
+    // Ties keep their order.
+    let mut list: TreeList<_> = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]
+        .into_iter()
+        .collect();
+    list.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(list.iter().map(|x| x.1).collect::<String>(), "ebdac");
+
+    let mut seed = 7u64;
+    let mut list: TreeList<u32> = (0..1000)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 100) as u32
+        })
+        .collect();
+    let mut expected: Vec<_> = list.iter().copied().collect();
+    expected.sort();
+    list.sort_by(|a, b| b.cmp(a));
+    list.sort();
+    check_tree(&list, &expected);
+
+    // Still a working treap afterwards, halves included.
+    let mut back = list.split_off(500);
+    list.insert(0, 1000);
+    back.push_front(1001);
+    list.append(&mut back);
+    assert_eq!(list.len(), 1002);
+    assert_eq!(list.get(0), Some(&1000));
+    assert_eq!(list.get(501), Some(&1001));
+
+    // A panicking comparison leaves every element in the list.
+    let mut list: TreeList<u32> = [4, 2, 6, 1, 5, 3].into_iter().collect();
+    let mut calls = 0;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.sort_by(|a, b| {
+            calls += 1;
+            assert!(calls < 5);
+            a.cmp(b)
+        })
+    }));
+    assert!(result.is_err());
+    let mut rest: Vec<_> = list.iter().copied().collect();
+    rest.sort();
+    check_tree(&list, &list.iter().copied().collect::<Vec<_>>());
+    assert_eq!(rest, &[1, 2, 3, 4, 5, 6]);
+
+    let mut list: TreeList<u32> = TreeList::new();
+    list.sort();
+    assert!(list.is_empty());
+}
+
+fn check_text(buffer: &TextBuffer, expected: &str) {
+    assert_eq!(buffer, expected);
+    assert_eq!(buffer.len_bytes(), expected.len());
+    assert_eq!(buffer.len_chars(), expected.chars().count());
+    assert_eq!(buffer.len_lines(), expected.split('\n').count());
+    assert!(buffer.lines().eq(expected.split('\n').map(String::from)));
+    assert!(buffer.chars().rev().eq(expected.chars().rev()));
+    assert!(buffer.chunks().all(|chunk| !chunk.is_empty()));
+}
+
+#[test]
+fn test_text_random_edits() {
+    let mut seed = 0x853c49e6748fea9bu64;
+    let mut rand = move |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+    let words = [
+        "fn",
+        " ",
+        "\n",
+        "héllo",
+        "wörld_1",
+        "🦀",
+        ", ",
+        "\n\n",
+        "日本語",
+    ];
+
+    let mut buffer = TextBuffer::new();
+    let mut expected = String::new();
+    for round in 0..1000 {
+        let chars = expected.chars().count();
+        match rand(5) {
+            0..=2 => {
+                let text: String = (0..rand(60)).map(|_| words[rand(words.len())]).collect();
+                let at = expected
+                    .char_indices()
+                    .nth(rand(chars + 1))
+                    .map_or(expected.len(), |(i, _)| i);
+                buffer.insert(at, &text);
+                expected.insert_str(at, &text);
+            }
+            3 => {
+                let start = rand(chars + 1);
+                let end = (start + rand(150)).min(chars);
+                let from = buffer.char_to_byte(start);
+                let to = buffer.char_to_byte(end);
+                assert_eq!(buffer.remove_chars(start..end), &expected[from..to]);
+                expected.replace_range(from..to, "");
+            }
+            _ => {
+                let at = expected
+                    .char_indices()
+                    .nth(rand(chars + 1))
+                    .map_or(expected.len(), |(i, _)| i);
+                let mut tail = buffer.split_off(at);
+                assert_eq!(tail, &expected[at..]);
+                check_text(&buffer, &expected[..at]);
+                buffer.append(&mut tail);
+                assert!(tail.is_empty());
+            }
+        }
+        if round % 100 == 0 {
+            check_text(&buffer, &expected);
+        }
+    }
+    check_text(&buffer, &expected);
+    assert!(buffer.len_bytes() > 10_000);
+
+    for (char_index, (byte, _)) in expected.char_indices().enumerate().step_by(211) {
+        assert_eq!(buffer.char_to_byte(char_index), byte);
+        assert_eq!(buffer.byte_to_char(byte), char_index);
+
+        let line = expected[..byte].matches('\n').count();
+        let line_start = expected[..byte].rfind('\n').map_or(0, |i| i + 1);
+        let col = expected[line_start..byte].chars().count();
+        assert_eq!(buffer.byte_to_line_col(byte), (line, col));
+        assert_eq!(buffer.line_to_byte(line), line_start);
+        assert_eq!(buffer.line_col_to_byte(line, col), byte);
+    }
+    assert!(!buffer.is_char_boundary(expected.find('🦀').unwrap() + 1));
+}
+
+#[test]
+fn test_text_lines() {
+    use std::fmt::Write;
+
+    let mut buffer = TextBuffer::from_lines(["first", "", "thïrd"]);
+    assert_eq!(buffer.len_lines(), 3);
+    assert_eq!(buffer.line(1), "");
+    assert_eq!(buffer.line(2), "thïrd");
+    assert_eq!(buffer.line_col_to_byte(0, 100), 5);
+    assert_eq!(buffer.byte_to_line_col(buffer.len_bytes()), (2, 5));
+
+    write!(buffer, "\n{}", 4).unwrap();
+    buffer.insert_char(0, 'ß');
+    assert_eq!(buffer.to_string(), "ßfirst\n\nthïrd\n4");
+    assert_eq!(format!("{:?}", buffer), "\"ßfirst\\n\\nthïrd\\n4\"");
+    assert_eq!(buffer.slice(2..7), "first");
+    assert_eq!(buffer.remove(0..2), "ß");
+
+    let empty = TextBuffer::new();
+    assert_eq!(empty.len_lines(), 1);
+    assert!(empty.lines().eq([String::new()]));
+    assert_eq!(empty, TextBuffer::from(""));
+}
+
+#[test]
+fn test_text_char_indices() {
+    let mut buffer = TextBuffer::from("añ🦀");
+    buffer.insert_char_at(2, 'é');
+    buffer.insert_char_at(4, '!');
+    buffer.insert_char_at(0, '€');
+    assert_eq!(buffer, "€añé🦀!");
+    assert_eq!(buffer.remove_char_at(4), '🦀');
+    assert_eq!(buffer.remove_char_at(0), '€');
+    assert_eq!(buffer, "añé!");
+
+    // Enough multi-byte text to span several chunks, checked against a
+    // `Vec<char>` of the same edits.
+    let mut expected: Vec<char> = "ü🦀ß".chars().cycle().take(3000).collect();
+    let mut buffer = TextBuffer::from(expected.iter().collect::<String>().as_str());
+    for i in 0..200 {
+        let at = (i * 37) % (expected.len() + 1);
+        let c = ['a', 'ñ', '中', '🦀'][i % 4];
+        buffer.insert_char_at(at, c);
+        expected.insert(at, c);
+
+        let at = (i * 53) % expected.len();
+        assert_eq!(buffer.remove_char_at(at), expected.remove(at));
+    }
+    assert_eq!(buffer.len_chars(), expected.len());
+    assert!(buffer.chars().eq(expected.iter().copied()));
+}
+
+#[test]
+#[should_panic]
+fn test_text_remove_char_past_end() {
+    let mut buffer = TextBuffer::from("🦀");
+    buffer.remove_char_at(1);
+}
+
+#[test]
+#[should_panic]
+fn test_text_split_inside_char() {
+    let mut buffer = TextBuffer::from("a🦀b");
+    buffer.split_off(2);
+}
+
+#[test]
+fn test_text_cursor() {
+    let mut buffer = TextBuffer::from("let x = 1;\nfn main() {\n    ok\n}");
+    let mut cursor = buffer.cursor_mut();
+    assert_eq!(cursor.peek_prev(), None);
+    assert!(cursor.move_next_word());
+    assert_eq!(cursor.position(), 3);
+    assert!(cursor.move_next_word());
+    assert_eq!(cursor.peek_prev(), Some('x'));
+
+    // The column sticks across the short line.
+    assert!(cursor.move_next_char());
+    assert!(cursor.move_next_char());
+    assert!(cursor.move_next_char());
+    assert_eq!(cursor.line_col(), (0, 8));
+    assert!(cursor.move_next_line());
+    assert_eq!(cursor.line_col(), (1, 8));
+    assert!(cursor.move_next_line());
+    assert_eq!(cursor.line_col(), (2, 6));
+    assert!(cursor.move_next_line());
+    assert_eq!(cursor.line_col(), (3, 1));
+    assert!(!cursor.move_next_line());
+    assert!(cursor.move_prev_line());
+    assert!(cursor.move_prev_line());
+    assert_eq!(cursor.line_col(), (1, 8));
+
+    cursor.move_line_end();
+    assert_eq!(cursor.peek_prev(), Some('{'));
+    assert!(cursor.move_prev_word());
+    assert_eq!(cursor.peek_next(), Some('m'));
+    cursor.insert("pub ");
+    cursor.move_line_start();
+    assert_eq!(cursor.delete_prev(), Some('\n'));
+    assert_eq!(cursor.delete_next(), Some('f'));
+    cursor.insert("Ω");
+    assert_eq!(cursor.line_col(), (0, 11));
+    assert!(cursor.move_prev_char());
+    assert_eq!(cursor.peek_next(), Some('Ω'));
+
+    cursor.seek(cursor.as_buffer().len_bytes());
+    assert!(!cursor.move_next_char());
+    assert!(!cursor.move_next_word());
+    assert_eq!(cursor.delete_next(), None);
+    cursor.seek_line_col(1, 4);
+    assert_eq!(cursor.peek_next(), Some('o'));
+
+    assert_eq!(buffer, "let x = 1;Ωn pub main() {\n    ok\n}");
+}
+
+fn exercise_deque<D: Deque<i32> + SplittableDeque<i32> + CursorDeque<i32>>() {
+    let mut deque = D::default();
+    assert!(deque.is_empty());
+    deque.push_back(2);
+    deque.push_front(1);
+    deque.extend([3, 4, 5]);
+    *deque.back_mut().unwrap() += 10;
+    assert_eq!(deque.front(), Some(&1));
+    assert_eq!(deque.back(), Some(&15));
+    assert!(deque.iter().rev().eq(&[15, 4, 3, 2, 1]));
+
+    let mut tail = deque.split_off(3);
+    assert!(tail.iter().eq(&[4, 15]));
+    tail.append(&mut deque);
+    assert!(deque.is_empty());
+    assert_eq!(tail.pop_front(), Some(4));
+    assert_eq!(tail.pop_back(), Some(3));
+    tail.clear();
+    assert_eq!(tail.len(), 0);
+
+    deque.extend(0..6);
+    {
+        let mut cursor = deque.cursor_mut();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(5));
+        assert_eq!(cursor.current(), Some(&mut 5));
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        cursor.move_next();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 2));
+        cursor.insert_before(10);
+        cursor.insert_after(11);
+        assert_eq!(cursor.index(), Some(3));
+        assert_eq!(cursor.remove_current(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 11));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 1));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        cursor.insert_before(20);
+        cursor.insert_after(21);
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(20));
+        assert_eq!(cursor.index(), None);
+    }
+    assert!(deque.iter().eq(&[21, 0, 1, 10, 11, 3, 4, 5]));
+
+    algorithms::rotate_left(&mut deque, 3);
+    assert!(deque.iter().eq(&[10, 11, 3, 4, 5, 21, 0, 1]));
+    algorithms::rotate_right(&mut deque, 11);
+    assert!(deque.iter().eq(&[21, 0, 1, 10, 11, 3, 4, 5]));
+    algorithms::rotate_left_split(&mut deque, 7);
+    assert!(deque.iter().eq(&[5, 21, 0, 1, 10, 11, 3, 4]));
+
+    algorithms::sort(&mut deque);
+    assert!(deque.iter().eq(&[0, 1, 3, 4, 5, 10, 11, 21]));
+    let mut odd = D::default();
+    odd.extend([-1, 3, 7]);
+    let merged = algorithms::merge(deque, odd);
+    assert!(merged.iter().eq(&[-1, 0, 1, 3, 3, 4, 5, 7, 10, 11, 21]));
+}
+
+#[test]
+fn test_deque_trait() {
+    exercise_deque::<LinkedList<i32>>();
+    exercise_deque::<std::collections::VecDeque<i32>>();
+    exercise_deque::<std::collections::LinkedList<i32>>();
+}
+
+#[test]
+fn test_deque_algorithms() {
+    use std::collections::VecDeque;
+
+    let mut sums = Vec::new();
+    algorithms::windows(1..=6, 3, |window: &LinkedList<i32>| {
+        sums.push(window.iter().sum::<i32>())
+    });
+    assert_eq!(sums, [6, 9, 12, 15]);
+
+    let values = [4, 2, 12, 3, 3, 8, 1, 9, 7];
+    let max = algorithms::sliding_max::<_, LinkedList<_>, _>(values, 3);
+    let min = algorithms::sliding_min::<_, VecDeque<_>, _>(values, 3);
+    for (i, window) in values.windows(3).enumerate() {
+        assert_eq!(max[i], *window.iter().max().unwrap());
+        assert_eq!(min[i], *window.iter().min().unwrap());
+    }
+    assert_eq!(max.len(), values.len() - 2);
+
+    // Stable on ties, and sorting keeps equal keys in order.
+    let a: LinkedList<(i32, char)> = [(1, 'a'), (3, 'a')].into_iter().collect();
+    let b: LinkedList<(i32, char)> = [(1, 'b'), (2, 'b')].into_iter().collect();
+    let merged = algorithms::merge_by(a, b, |x, y| x.0.cmp(&y.0));
+    assert!(merged.iter().eq(&[(1, 'a'), (1, 'b'), (2, 'b'), (3, 'a')]));
+
+    let mut pairs: std::collections::LinkedList<(i32, usize)> =
+        (0..50).map(|i| ((i * 7) % 5, i as usize)).collect();
+    algorithms::sort_by(&mut pairs, |x, y| x.0.cmp(&y.0));
+    assert!(pairs
+        .iter()
+        .zip(pairs.iter().skip(1))
+        .all(|(x, y)| x.0 < y.0 || (x.0 == y.0 && x.1 < y.1)));
+}
+
+#[test]
+fn test_stack() {
+    let mut stack = Stack::new();
+    assert_eq!(stack.pop_front(), None);
+    stack.extend([1, 2, 3]);
+    assert_eq!(stack.front(), Some(&3));
+    *stack.front_mut().unwrap() = 30;
+    assert!(stack.iter().eq(&[30, 2, 1]));
+    assert_eq!(stack.pop_front(), Some(30));
+    stack.reverse();
+    assert!(stack.iter().eq(&[1, 2]));
+
+    let mut stack: Stack<i32> = (0..6).rev().collect();
+    assert_eq!(stack.clone(), stack);
+    for elem in stack.iter_mut() {
+        *elem *= 10;
+    }
+
+    let mut cursor = stack.cursor_mut();
+    assert_eq!(cursor.peek_next(), Some(&mut 0));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(1));
+    let tail = cursor.split_after();
+    assert!(tail.iter().eq(&[20, 30, 40, 50]));
+    assert_eq!(cursor.remove_current(), Some(10));
+    assert_eq!(cursor.index(), None);
+    cursor.splice_after(tail);
+    cursor.insert_after(-1);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 20));
+    assert_eq!(format!("{:?}", stack), "[-1, 20, 30, 40, 50, 0]");
+
+    let list: LinkedList<i32> = stack.into_iter().collect();
+    assert_eq!(list.back(), Some(&0));
+    assert_eq!(list.len(), 6);
+}
+
+#[test]
+fn test_queue() {
+    let mut queue: Queue<i32> = (1..=3).collect();
+    queue.push_front(0);
+    queue.push_back(4);
+    assert_eq!(queue.front(), Some(&0));
+    assert_eq!(queue.back(), Some(&4));
+    assert_eq!(queue.pop_front(), Some(0));
+    assert_eq!(queue.iter().len(), 4);
+
+    let mut other: Queue<i32> = (5..7).collect();
+    queue.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(other.back(), None);
+    queue.reverse();
+    assert_eq!(queue.back(), Some(&1));
+    queue.push_back(0);
+    assert!(queue.iter().eq(&[6, 5, 4, 3, 2, 1, 0]));
+
+    // Every edit at the end has to keep the tail pointer right.
+    let mut cursor = queue.cursor_mut();
+    for _ in 0..6 {
+        cursor.move_next();
+    }
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.current(), Some(&mut 0));
+    assert_eq!(cursor.remove_current(), Some(0));
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_next();
+    let tail = cursor.split_after();
+    assert!(tail.iter().eq(&[4, 3, 2]));
+    assert_eq!(tail.back(), Some(&2));
+    cursor.insert_after(100);
+    cursor.move_next();
+    cursor.splice_after(tail);
+    assert_eq!(queue.back(), Some(&2));
+    queue.push_back(7);
+    assert!(queue.iter().eq(&[6, 5, 100, 4, 3, 2, 7]));
+
+    let mut cursor = queue.cursor_mut();
+    let all = cursor.split_after();
+    assert_eq!(all.len(), 7);
+    assert_eq!(queue.back(), None);
+    queue.push_back(1);
+    assert_eq!(queue.front(), Some(&1));
+
+    // Stack and queue share nodes, so converting keeps the order.
+    let stack = Stack::from(all);
+    assert_eq!(stack.front(), Some(&6));
+    let mut queue = Queue::from(stack);
+    assert_eq!(queue.back(), Some(&7));
+    queue.push_back(8);
+    assert!(queue.iter().eq(&[6, 5, 100, 4, 3, 2, 7, 8]));
+}
+
+#[test]
+fn test_xor_basic() {
+    let mut list = XorLinkedList::new();
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+
+    let mut expected = std::collections::VecDeque::new();
+    for i in 0..100 {
+        match i % 5 {
+            0 | 1 => {
+                list.push_back(i);
+                expected.push_back(i);
+            }
+            2 => {
+                list.push_front(i);
+                expected.push_front(i);
+            }
+            3 => assert_eq!(list.pop_back(), expected.pop_back()),
+            _ => assert_eq!(list.pop_front(), expected.pop_front()),
+        }
+        if i % 17 == 0 {
+            list.reverse();
+            expected.make_contiguous().reverse();
+        }
+        check_links(&list);
+        assert!(list.iter().eq(expected.iter()));
+    }
+
+    *list.front_mut().unwrap() += 1000;
+    *list.back_mut().unwrap() += 1000;
+    assert_eq!(list.front(), Some(&(expected[0] + 1000)));
+    assert_eq!(list.back(), Some(&(expected[expected.len() - 1] + 1000)));
+
+    let mut iter = list.iter_mut();
+    let (a, b) = (iter.next().unwrap(), iter.next_back().unwrap());
+    std::mem::swap(a, b);
+    assert_eq!(iter.len(), expected.len() - 2);
+    check_links(&list);
+
+    assert_eq!(list.clone(), list);
+    let reversed: Vec<_> = list.clone().into_iter().rev().collect();
+    list.reverse();
+    assert!(list.iter().eq(reversed.iter()));
+}
+
+#[test]
+fn test_xor_cursor() {
+    let mut list: XorLinkedList<i32> = (1..=6).collect();
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 6));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(5));
+    assert_eq!(cursor.peek_prev(), Some(&mut 5));
+    assert_eq!(cursor.peek_next(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.peek_prev(), Some(&mut 1));
+
+    cursor.insert_before(10);
+    cursor.insert_after(11);
+    assert_eq!(cursor.index(), Some(2));
+    assert_eq!(cursor.peek_prev(), Some(&mut 10));
+    assert_eq!(cursor.peek_next(), Some(&mut 11));
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 11));
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(1));
+    assert_eq!(cursor.current(), Some(&mut 10));
+    assert_eq!(cursor.index(), Some(0));
+    cursor.insert_before(20);
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    cursor.insert_before(30);
+    cursor.insert_after(31);
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(30));
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.as_list().len(), 8);
+    check_links(&list);
+    assert!(list.iter().eq(&[31, 20, 10, 11, 3, 4, 5, 6]));
+
+    // The cursor reads the same links from either end.
+    list.reverse();
+    let mut cursor = list.cursor_mut();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 5));
+    cursor.insert_after(50);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(4));
+    check_links(&list);
+    assert_eq!(format!("{:?}", list), "[6, 5, 50, 3, 11, 10, 20, 31]");
+}
+
+#[test]
+fn test_array_list_basic() {
+    let mut list: ArrayLinkedList<String, 4> = ArrayLinkedList::new();
+    assert_eq!(list.capacity(), 4);
+    assert_eq!(list.pop_back(), None);
+    for word in ["b", "c", "d"] {
+        assert_eq!(list.push_back(word.to_string()), Ok(()));
+    }
+    assert_eq!(list.push_front("a".to_string()), Ok(()));
+    assert!(list.is_full());
+    assert_eq!(list.push_back("e".to_string()), Err("e".to_string()));
+    assert_eq!(list.push_front("z".to_string()), Err("z".to_string()));
+    check_links(&list);
+
+    // Freed slots get reused, in whatever order they come back.
+    assert_eq!(list.pop_front().as_deref(), Some("a"));
+    assert_eq!(list.pop_back().as_deref(), Some("d"));
+    list.push_back("x".to_string()).unwrap();
+    list.push_front("y".to_string()).unwrap();
+    list.front_mut().unwrap().push('!');
+    list.back_mut().unwrap().push('?');
+    assert_eq!(format!("{:?}", list), r#"["y!", "b", "c", "x?"]"#);
+    check_links(&list);
+
+    for elem in list.iter_mut().rev() {
+        elem.insert(0, '-');
+    }
+    assert_eq!(list.front().map(String::as_str), Some("-y!"));
+    assert_eq!(list.clone(), list);
+
+    // Moving the list moves the array, and index links don't care.
+    let moved = Box::new(list);
+    let drained: Vec<_> = moved.into_iter().rev().collect();
+    assert_eq!(drained, ["-x?", "-c", "-b", "-y!"]);
+
+    let mut empty: ArrayLinkedList<i32, 0> = ArrayLinkedList::new();
+    assert_eq!(empty.push_back(1), Err(1));
+    assert!(empty.is_full() && empty.is_empty());
+}
+
+#[test]
+fn test_array_list_append_split_ord() {
+    let mut list: ArrayLinkedList<i32, 6> = (1..=4).collect();
+    let back = list.split_off(1);
+    assert!(list.iter().eq(&[1]));
+    assert!(back.iter().eq(&[2, 3, 4]));
+    check_links(&back);
+    assert!(list.split_off(1).is_empty());
+
+    let mut list = back;
+    list.extend([5, 6, 7]);
+    assert!(list.is_full());
+    let rest: ArrayLinkedList<i32, 6> = [8].into_iter().collect();
+    let rest = list.append(rest).unwrap_err();
+    assert!(rest.iter().eq(&[8]));
+    assert_eq!(list.len(), 6);
+
+    let tail = list.split_off(3);
+    assert_eq!(list.append(tail), Ok(()));
+    assert!(list.iter().eq(&[2, 3, 4, 5, 6, 7]));
+    check_links(&list);
+
+    let smaller: ArrayLinkedList<i32, 6> = [2, 3].into_iter().collect();
+    let bigger: ArrayLinkedList<i32, 6> = [2, 4].into_iter().collect();
+    assert!(smaller < list && list < bigger);
+    assert_eq!(list.cmp(&list.clone()), std::cmp::Ordering::Equal);
+}
+
+#[test]
+#[should_panic]
+fn test_array_list_extend_past_capacity() {
+    let mut list: ArrayLinkedList<i32, 2> = ArrayLinkedList::new();
+    list.extend(0..3);
+}
+
+#[test]
+fn test_array_list_cursor() {
+    let mut list: ArrayLinkedList<i32, 8> = ArrayLinkedList::new();
+    for i in 1..=5 {
+        list.push_back(i).unwrap();
+    }
+
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 5));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.insert_before(10), Ok(()));
+    assert_eq!(cursor.insert_after(11), Ok(()));
+    assert_eq!(cursor.index(), Some(2));
+    assert_eq!(cursor.remove_current(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 11));
+
+    let back = cursor.split_after();
+    assert!(back.iter().eq(&[3, 4, 5]));
+    let front = cursor.split_before();
+    assert!(front.iter().eq(&[1, 10]));
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(cursor.splice_after(back), Ok(()));
+    assert_eq!(cursor.splice_before(front), Ok(()));
+    assert_eq!(cursor.index(), Some(2));
+    assert!(cursor.as_list().iter().eq(&[1, 10, 11, 3, 4, 5]));
+
+    let mut extra = ArrayLinkedList::new();
+    for i in 20..23 {
+        extra.push_back(i).unwrap();
+    }
+    let extra = cursor.splice_after(extra).unwrap_err();
+    assert_eq!(extra.len(), 3);
+
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.insert_before(6), Ok(()));
+    assert_eq!(cursor.insert_after(0), Ok(()));
+    assert_eq!(cursor.insert_after(-1), Err(-1));
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(6));
+    assert_eq!(cursor.index(), None);
+    check_links(&list);
+    assert!(list.iter().eq(&[0, 1, 10, 11, 3, 4, 5]));
+
+    let mut cursor = list.cursor_mut();
+    assert_eq!(cursor.split_before().len(), 7);
+    assert!(list.is_empty());
+}
@@ -0,0 +1,793 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use core::fmt::{self, Debug, Display};
+use core::mem;
+use core::ops::Range;
+
+use crate::linked_list::{self, LinkedList};
+
+// Chunks are capped so an edit only ever rewrites one small string. They're
+// never empty, and every chunk boundary is also a char boundary.
+const CHUNK_MAX: usize = 1024;
+
+#[derive(Clone)]
+struct Chunk {
+    text: String,
+    chars: usize,
+    newlines: usize,
+}
+
+// Text split across a list of chunks, indexed by byte like `str`. Lines are
+// separated by '\n', so there's always one more line than there are newlines.
+#[derive(Clone, Default)]
+pub struct TextBuffer {
+    chunks: LinkedList<Chunk>,
+    bytes: usize,
+    chars: usize,
+    newlines: usize,
+}
+
+pub struct Chunks<'a> {
+    iter: linked_list::Iter<'a, Chunk>,
+    front: usize,
+    back: usize,
+    range: Range<usize>,
+}
+
+pub struct Lines<'a> {
+    chunks: Chunks<'a>,
+    rest: &'a str,
+    done: bool,
+}
+
+// Sits between two chars, at a byte offset. Moving up and down remembers the
+// column it started from, so passing a short line doesn't lose it.
+pub struct CursorMut<'a> {
+    buffer: &'a mut TextBuffer,
+    byte: usize,
+    column: Option<usize>,
+}
+
+impl Chunk {
+    fn new(text: String) -> Self {
+        let mut chunk = Self {
+            text,
+            chars: 0,
+            newlines: 0,
+        };
+        chunk.refresh();
+        chunk
+    }
+
+    fn refresh(&mut self) {
+        self.chars = self.text.chars().count();
+        self.newlines = newlines(self.text.as_bytes());
+    }
+}
+
+fn newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// The last char boundary at or before `index`.
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
+    }
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn pieces(mut text: &str) -> impl Iterator<Item = &str> {
+    core::iter::from_fn(move || {
+        if text.is_empty() {
+            return None;
+        }
+        let (piece, rest) = text.split_at(floor_boundary(text, CHUNK_MAX));
+        text = rest;
+        Some(piece)
+    })
+}
+
+// Leaves a cursor that started on the ghost on the chunk holding `byte`, and
+// returns the offset into it. Past the last chunk it ends up on the ghost.
+fn seek(cursor: &mut linked_list::CursorMut<'_, Chunk>, mut byte: usize) -> usize {
+    cursor.move_next();
+    while let Some(chunk) = cursor.current() {
+        if byte < chunk.text.len() {
+            break;
+        }
+        byte -= chunk.text.len();
+        cursor.move_next();
+    }
+    byte
+}
+
+// Folds the chunk before the cursor into the one under it, if they fit.
+fn coalesce(cursor: &mut linked_list::CursorMut<'_, Chunk>) {
+    let Some(prev) = cursor.peek_prev() else {
+        return;
+    };
+    let prev_len = prev.text.len();
+    match cursor.current() {
+        Some(current) if prev_len + current.text.len() <= CHUNK_MAX => {}
+        _ => return,
+    }
+
+    cursor.move_prev();
+    let prev = cursor.remove_current().unwrap();
+    let current = cursor.current().unwrap();
+    current.text.insert_str(0, &prev.text);
+    current.chars += prev.chars;
+    current.newlines += prev.newlines;
+}
+
+impl TextBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_lines<I, S>(lines: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut buffer = Self::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                buffer.push_str("\n");
+            }
+            buffer.push_str(line.as_ref());
+        }
+        buffer
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.chars
+    }
+
+    pub fn len_lines(&self) -> usize {
+        self.newlines + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn is_char_boundary(&self, mut byte: usize) -> bool {
+        if byte >= self.bytes {
+            return byte == self.bytes;
+        }
+        for chunk in self.chunks.iter() {
+            if byte < chunk.text.len() {
+                return chunk.text.is_char_boundary(byte);
+            }
+            byte -= chunk.text.len();
+        }
+        unreachable!()
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        self.grow(text);
+
+        let mut rest = text;
+        if let Some(last) = self.chunks.back_mut() {
+            let room = CHUNK_MAX.saturating_sub(last.text.len());
+            let (head, tail) = rest.split_at(floor_boundary(rest, room));
+            if !head.is_empty() {
+                last.text.push_str(head);
+                last.refresh();
+            }
+            rest = tail;
+        }
+        for piece in pieces(rest) {
+            self.chunks.push_back(Chunk::new(piece.to_owned()));
+        }
+    }
+
+    pub fn insert(&mut self, byte: usize, text: &str) {
+        assert!(
+            self.is_char_boundary(byte),
+            "byte index {} is not a char boundary (len is {})",
+            byte,
+            self.bytes
+        );
+        if byte == self.bytes {
+            return self.push_str(text);
+        }
+
+        let mut cursor = self.chunks.cursor_mut();
+        let offset = seek(&mut cursor, byte);
+        let chunk = cursor.current().unwrap();
+        chunk.text.insert_str(offset, text);
+
+        if chunk.text.len() <= CHUNK_MAX {
+            chunk.refresh();
+        } else {
+            let joined = mem::take(&mut chunk.text);
+            let mut split = pieces(&joined).map(|piece| Chunk::new(piece.to_owned()));
+            *chunk = split.next().unwrap();
+            for piece in split {
+                cursor.insert_after(piece);
+                cursor.move_next();
+            }
+        }
+        self.grow(text);
+    }
+
+    pub fn insert_char(&mut self, byte: usize, c: char) {
+        self.insert(byte, c.encode_utf8(&mut [0; 4]));
+    }
+
+    // Like `insert_char`, but `char_index` counts chars rather than bytes.
+    pub fn insert_char_at(&mut self, char_index: usize, c: char) {
+        let byte = self.char_to_byte(char_index);
+        self.insert_char(byte, c);
+    }
+
+    pub fn remove(&mut self, range: Range<usize>) -> String {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.bytes,
+            "range {}..{} out of bounds for len {}",
+            start,
+            end,
+            self.bytes
+        );
+        assert!(
+            self.is_char_boundary(start) && self.is_char_boundary(end),
+            "range {}..{} does not fall on char boundaries",
+            start,
+            end
+        );
+
+        let mut removed = String::with_capacity(end - start);
+        if start == end {
+            return removed;
+        }
+
+        let mut cursor = self.chunks.cursor_mut();
+        let mut offset = seek(&mut cursor, start);
+        while removed.len() < end - start {
+            let chunk = cursor.current().unwrap();
+            let take = (chunk.text.len() - offset).min(end - start - removed.len());
+            removed.push_str(&chunk.text[offset..offset + take]);
+            chunk.text.replace_range(offset..offset + take, "");
+
+            if chunk.text.is_empty() {
+                cursor.remove_current();
+            } else {
+                chunk.refresh();
+                cursor.move_next();
+            }
+            offset = 0;
+        }
+        coalesce(&mut cursor);
+
+        self.shrink(&removed);
+        removed
+    }
+
+    pub fn remove_chars(&mut self, range: Range<usize>) -> String {
+        let start = self.char_to_byte(range.start);
+        let end = self.char_to_byte(range.end);
+        self.remove(start..end)
+    }
+
+    pub fn remove_char_at(&mut self, char_index: usize) -> char {
+        assert!(
+            char_index < self.chars,
+            "char index (is {}) should be < len (is {})",
+            char_index,
+            self.chars
+        );
+        let removed = self.remove_chars(char_index..char_index + 1);
+        removed.chars().next().unwrap()
+    }
+
+    // Splits at a char boundary, keeping everything before `at`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(
+            self.is_char_boundary(at),
+            "byte index {} is not a char boundary (len is {})",
+            at,
+            self.bytes
+        );
+
+        let mut cursor = self.chunks.cursor_mut();
+        let offset = seek(&mut cursor, at);
+        let chunks = if offset == 0 {
+            let front = cursor.split_before();
+            mem::replace(&mut self.chunks, front)
+        } else {
+            let chunk = cursor.current().unwrap();
+            let text = chunk.text.split_off(offset);
+            chunk.refresh();
+
+            let mut tail = cursor.split_after();
+            tail.push_front(Chunk::new(text));
+            tail
+        };
+
+        let tail = Self::from_chunks(chunks);
+        self.bytes -= tail.bytes;
+        self.chars -= tail.chars;
+        self.newlines -= tail.newlines;
+        tail
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        let mut other = mem::take(other);
+        self.bytes += other.bytes;
+        self.chars += other.chars;
+        self.newlines += other.newlines;
+        self.chunks.append(&mut other.chunks);
+    }
+
+    pub fn char_to_byte(&self, char_index: usize) -> usize {
+        assert!(
+            char_index <= self.chars,
+            "char index (is {}) should be <= len (is {})",
+            char_index,
+            self.chars
+        );
+
+        let (mut rest, mut byte) = (char_index, 0);
+        for chunk in self.chunks.iter() {
+            if rest < chunk.chars {
+                return byte + chunk.text.char_indices().nth(rest).unwrap().0;
+            }
+            rest -= chunk.chars;
+            byte += chunk.text.len();
+        }
+        byte
+    }
+
+    pub fn byte_to_char(&self, byte: usize) -> usize {
+        assert!(
+            self.is_char_boundary(byte),
+            "byte index {} is not a char boundary (len is {})",
+            byte,
+            self.bytes
+        );
+
+        let (mut rest, mut chars) = (byte, 0);
+        for chunk in self.chunks.iter() {
+            if rest < chunk.text.len() {
+                return chars + chunk.text[..rest].chars().count();
+            }
+            rest -= chunk.text.len();
+            chars += chunk.chars;
+        }
+        chars
+    }
+
+    // Where `line` starts, in bytes.
+    pub fn line_to_byte(&self, line: usize) -> usize {
+        assert!(
+            line < self.len_lines(),
+            "line (is {}) should be < line count (is {})",
+            line,
+            self.len_lines()
+        );
+        if line == 0 {
+            return 0;
+        }
+
+        let (mut rest, mut byte) = (line, 0);
+        for chunk in self.chunks.iter() {
+            if rest <= chunk.newlines {
+                let (at, _) = chunk.text.match_indices('\n').nth(rest - 1).unwrap();
+                return byte + at + 1;
+            }
+            rest -= chunk.newlines;
+            byte += chunk.text.len();
+        }
+        unreachable!()
+    }
+
+    pub fn byte_to_line(&self, byte: usize) -> usize {
+        assert!(
+            byte <= self.bytes,
+            "byte index (is {}) should be <= len (is {})",
+            byte,
+            self.bytes
+        );
+
+        let (mut rest, mut line) = (byte, 0);
+        for chunk in self.chunks.iter() {
+            if rest < chunk.text.len() {
+                return line + newlines(&chunk.text.as_bytes()[..rest]);
+            }
+            rest -= chunk.text.len();
+            line += chunk.newlines;
+        }
+        line
+    }
+
+    // Columns count chars from the start of the line.
+    pub fn byte_to_line_col(&self, byte: usize) -> (usize, usize) {
+        assert!(
+            self.is_char_boundary(byte),
+            "byte index {} is not a char boundary (len is {})",
+            byte,
+            self.bytes
+        );
+
+        let line = self.byte_to_line(byte);
+        let start = self.line_to_byte(line);
+        let col = self.chunks_in(start..byte).map(|s| s.chars().count()).sum();
+        (line, col)
+    }
+
+    // A column past the end of the line lands on the end of the line.
+    pub fn line_col_to_byte(&self, line: usize, col: usize) -> usize {
+        let start = self.line_to_byte(line);
+        start
+            + self
+                .chars_at(start)
+                .take(col)
+                .take_while(|&c| c != '\n')
+                .map(char::len_utf8)
+                .sum::<usize>()
+    }
+
+    // Without the trailing newline.
+    pub fn line(&self, line: usize) -> String {
+        let start = self.line_to_byte(line);
+        let end = if line + 1 < self.len_lines() {
+            self.line_to_byte(line + 1) - 1
+        } else {
+            self.bytes
+        };
+        self.slice(start..end)
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> String {
+        assert!(
+            self.is_char_boundary(range.start) && self.is_char_boundary(range.end),
+            "range {}..{} does not fall on char boundaries",
+            range.start,
+            range.end
+        );
+        self.chunks_in(range).collect()
+    }
+
+    pub fn chunks(&self) -> Chunks<'_> {
+        self.chunks_in(0..self.bytes)
+    }
+
+    pub fn chars(&self) -> impl DoubleEndedIterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    pub fn lines(&self) -> Lines<'_> {
+        Lines {
+            chunks: self.chunks(),
+            rest: "",
+            done: false,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_> {
+        CursorMut {
+            buffer: self,
+            byte: 0,
+            column: None,
+        }
+    }
+
+    fn from_chunks(chunks: LinkedList<Chunk>) -> Self {
+        let mut buffer = Self::new();
+        for chunk in chunks.iter() {
+            buffer.bytes += chunk.text.len();
+            buffer.chars += chunk.chars;
+            buffer.newlines += chunk.newlines;
+        }
+        buffer.chunks = chunks;
+        buffer
+    }
+
+    fn chunks_in(&self, range: Range<usize>) -> Chunks<'_> {
+        Chunks {
+            iter: self.chunks.iter(),
+            front: 0,
+            back: self.bytes,
+            range,
+        }
+    }
+
+    fn chars_at(&self, byte: usize) -> impl Iterator<Item = char> + '_ {
+        self.chunks_in(byte..self.bytes).flat_map(str::chars)
+    }
+
+    fn chars_before(&self, byte: usize) -> impl Iterator<Item = char> + '_ {
+        self.chunks_in(0..byte).rev().flat_map(|s| s.chars().rev())
+    }
+
+    fn grow(&mut self, text: &str) {
+        self.bytes += text.len();
+        self.chars += text.chars().count();
+        self.newlines += newlines(text.as_bytes());
+    }
+
+    fn shrink(&mut self, text: &str) {
+        self.bytes -= text.len();
+        self.chars -= text.chars().count();
+        self.newlines -= newlines(text.as_bytes());
+    }
+}
+
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> Self {
+        let mut buffer = Self::new();
+        buffer.push_str(text);
+        buffer
+    }
+}
+
+impl fmt::Write for TextBuffer {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.push_str(text);
+        Ok(())
+    }
+}
+
+impl Display for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+// Chunk boundaries don't matter, only the text.
+impl PartialEq for TextBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+            && self
+                .chunks()
+                .flat_map(str::bytes)
+                .eq(other.chunks().flat_map(str::bytes))
+    }
+}
+
+impl Eq for TextBuffer {}
+
+impl PartialEq<str> for TextBuffer {
+    fn eq(&self, other: &str) -> bool {
+        self.bytes == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for TextBuffer {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front < self.range.end {
+            let chunk = self.iter.next()?;
+            let start = self.front;
+            self.front += chunk.text.len();
+
+            let (lo, hi) = (self.range.start.max(start), self.range.end.min(self.front));
+            if lo < hi {
+                return Some(&chunk.text[lo - start..hi - start]);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> DoubleEndedIterator for Chunks<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.back > self.range.start {
+            let chunk = self.iter.next_back()?;
+            let end = self.back;
+            self.back -= chunk.text.len();
+
+            let (lo, hi) = (self.range.start.max(self.back), self.range.end.min(end));
+            if lo < hi {
+                return Some(&chunk.text[lo - self.back..hi - self.back]);
+            }
+        }
+        None
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut line = String::new();
+        loop {
+            if let Some(at) = self.rest.find('\n') {
+                line.push_str(&self.rest[..at]);
+                self.rest = &self.rest[at + 1..];
+                return Some(line);
+            }
+
+            line.push_str(self.rest);
+            match self.chunks.next() {
+                Some(chunk) => self.rest = chunk,
+                None => {
+                    self.done = true;
+                    return Some(line);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> CursorMut<'a> {
+    pub fn position(&self) -> usize {
+        self.byte
+    }
+
+    pub fn line_col(&self) -> (usize, usize) {
+        self.buffer.byte_to_line_col(self.byte)
+    }
+
+    pub fn as_buffer(&self) -> &TextBuffer {
+        self.buffer
+    }
+
+    pub fn seek(&mut self, byte: usize) {
+        assert!(
+            self.buffer.is_char_boundary(byte),
+            "byte index {} is not a char boundary (len is {})",
+            byte,
+            self.buffer.bytes
+        );
+        self.byte = byte;
+        self.column = None;
+    }
+
+    pub fn seek_line_col(&mut self, line: usize, col: usize) {
+        self.byte = self.buffer.line_col_to_byte(line, col);
+        self.column = None;
+    }
+
+    pub fn peek_next(&self) -> Option<char> {
+        self.buffer.chars_at(self.byte).next()
+    }
+
+    pub fn peek_prev(&self) -> Option<char> {
+        self.buffer.chars_before(self.byte).next()
+    }
+
+    pub fn move_next_char(&mut self) -> bool {
+        let Some(c) = self.peek_next() else {
+            return false;
+        };
+        self.byte += c.len_utf8();
+        self.column = None;
+        true
+    }
+
+    pub fn move_prev_char(&mut self) -> bool {
+        let Some(c) = self.peek_prev() else {
+            return false;
+        };
+        self.byte -= c.len_utf8();
+        self.column = None;
+        true
+    }
+
+    // Skips anything that isn't part of a word, then lands on the end of the
+    // word after it.
+    pub fn move_next_word(&mut self) -> bool {
+        let mut chars = self.buffer.chars_at(self.byte).peekable();
+        let mut moved = 0;
+        while let Some(c) = chars.next_if(|&c| !is_word(c)) {
+            moved += c.len_utf8();
+        }
+        while let Some(c) = chars.next_if(|&c| is_word(c)) {
+            moved += c.len_utf8();
+        }
+
+        self.byte += moved;
+        self.column = None;
+        moved > 0
+    }
+
+    // The mirror image, landing on the start of the word before.
+    pub fn move_prev_word(&mut self) -> bool {
+        let mut chars = self.buffer.chars_before(self.byte).peekable();
+        let mut moved = 0;
+        while let Some(c) = chars.next_if(|&c| !is_word(c)) {
+            moved += c.len_utf8();
+        }
+        while let Some(c) = chars.next_if(|&c| is_word(c)) {
+            moved += c.len_utf8();
+        }
+
+        self.byte -= moved;
+        self.column = None;
+        moved > 0
+    }
+
+    pub fn move_next_line(&mut self) -> bool {
+        let (line, col) = self.line_col();
+        if line + 1 >= self.buffer.len_lines() {
+            return false;
+        }
+        let col = *self.column.get_or_insert(col);
+        self.byte = self.buffer.line_col_to_byte(line + 1, col);
+        true
+    }
+
+    pub fn move_prev_line(&mut self) -> bool {
+        let (line, col) = self.line_col();
+        if line == 0 {
+            return false;
+        }
+        let col = *self.column.get_or_insert(col);
+        self.byte = self.buffer.line_col_to_byte(line - 1, col);
+        true
+    }
+
+    pub fn move_line_start(&mut self) {
+        let line = self.buffer.byte_to_line(self.byte);
+        self.byte = self.buffer.line_to_byte(line);
+        self.column = None;
+    }
+
+    pub fn move_line_end(&mut self) {
+        let line = self.buffer.byte_to_line(self.byte);
+        self.byte = self.buffer.line_col_to_byte(line, usize::MAX);
+        self.column = None;
+    }
+
+    // Leaves the cursor after the inserted text.
+    pub fn insert(&mut self, text: &str) {
+        self.buffer.insert(self.byte, text);
+        self.byte += text.len();
+        self.column = None;
+    }
+
+    pub fn delete_next(&mut self) -> Option<char> {
+        let c = self.peek_next()?;
+        self.buffer.remove(self.byte..self.byte + c.len_utf8());
+        self.column = None;
+        Some(c)
+    }
+
+    pub fn delete_prev(&mut self) -> Option<char> {
+        let c = self.peek_prev()?;
+        self.byte -= c.len_utf8();
+        self.buffer.remove(self.byte..self.byte + c.len_utf8());
+        self.column = None;
+        Some(c)
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::mem;
+
+use crate::linked_list::{LinkedList, NodeRef};
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+// Enough levels for any `u64` deadline.
+const LEVELS: usize = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(u64);
+
+struct Timer<T> {
+    deadline: u64,
+    level: usize,
+    slot: usize,
+    node: NodeRef<T>,
+}
+
+struct Level<T> {
+    slots: [LinkedList<T>; SLOTS],
+    occupied: u64,
+}
+
+// Hierarchical wheel over abstract ticks. A timer sits in the lowest level
+// where its deadline shares all the higher digits (base 64) with `elapsed`,
+// and gets cascaded down a level whenever `elapsed` reaches the start of its
+// slot. Level 0 slots are exact, so they can be spliced out whole on expiry.
+pub struct TimerWheel<T> {
+    levels: Box<[Level<T>]>,
+    timers: HashMap<u64, Timer<T>>,
+    ids: HashMap<NodeRef<T>, u64>,
+    elapsed: u64,
+    next_id: u64,
+}
+
+impl<T> TimerWheel<T> {
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    pub fn starting_at(elapsed: u64) -> Self {
+        Self {
+            levels: (0..LEVELS)
+                .map(|_| Level {
+                    slots: std::array::from_fn(|_| LinkedList::new()),
+                    occupied: 0,
+                })
+                .collect(),
+            timers: HashMap::new(),
+            ids: HashMap::new(),
+            elapsed,
+            next_id: 0,
+        }
+    }
+
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    // A delay of 0 fires on the next `advance`, whatever `now` it's given.
+    pub fn insert(&mut self, item: T, delay: u64) -> TimerHandle {
+        let deadline = self.elapsed.saturating_add(delay);
+        let (level, slot) = self.slot_for(deadline);
+
+        let node = self.levels[level].slots[slot].push_back_node(item);
+        self.levels[level].occupied |= 1 << slot;
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.insert(
+            id,
+            Timer {
+                deadline,
+                level,
+                slot,
+                node,
+            },
+        );
+        self.ids.insert(node, id);
+        TimerHandle(id)
+    }
+
+    pub fn contains(&self, handle: TimerHandle) -> bool {
+        self.timers.contains_key(&handle.0)
+    }
+
+    pub fn deadline(&self, handle: TimerHandle) -> Option<u64> {
+        self.timers.get(&handle.0).map(|timer| timer.deadline)
+    }
+
+    pub fn get(&self, handle: TimerHandle) -> Option<&T> {
+        let timer = self.timers.get(&handle.0)?;
+        let list = &self.levels[timer.level].slots[timer.slot];
+        unsafe { Some(list.node(timer.node)) }
+    }
+
+    pub fn get_mut(&mut self, handle: TimerHandle) -> Option<&mut T> {
+        let timer = self.timers.get(&handle.0)?;
+        let list = &mut self.levels[timer.level].slots[timer.slot];
+        unsafe { Some(list.node_mut(timer.node)) }
+    }
+
+    pub fn cancel(&mut self, handle: TimerHandle) -> Option<T> {
+        let timer = self.timers.remove(&handle.0)?;
+        self.ids.remove(&timer.node);
+
+        let level = &mut self.levels[timer.level];
+        let list = &mut level.slots[timer.slot];
+        let item = unsafe { list.remove_node(timer.node) };
+        if list.is_empty() {
+            level.occupied &= !(1 << timer.slot);
+        }
+        Some(item)
+    }
+
+    // Returns false if the timer already fired or was cancelled.
+    pub fn reset(&mut self, handle: TimerHandle, delay: u64) -> bool {
+        let deadline = self.elapsed.saturating_add(delay);
+        let (level, slot) = self.slot_for(deadline);
+        let Some(timer) = self.timers.get_mut(&handle.0) else {
+            return false;
+        };
+
+        unsafe {
+            let old = &mut self.levels[timer.level];
+            old.slots[timer.slot].unlink_node(timer.node);
+            if old.slots[timer.slot].is_empty() {
+                old.occupied &= !(1 << timer.slot);
+            }
+
+            self.levels[level].slots[slot].link_back(timer.node);
+            self.levels[level].occupied |= 1 << slot;
+        }
+
+        timer.deadline = deadline;
+        timer.level = level;
+        timer.slot = slot;
+        true
+    }
+
+    // Exact if the next timer is already on level 0, otherwise the start of
+    // the slot it's waiting in, which is never later than its deadline.
+    pub fn next_deadline(&self) -> Option<u64> {
+        self.next_expiration().map(|(_, _, deadline)| deadline)
+    }
+
+    // Moves time forward to `now` and hands back everything that expired on
+    // the way, in deadline order. Time never goes backwards, an earlier `now`
+    // only collects what's due already.
+    pub fn advance(&mut self, now: u64) -> LinkedList<T> {
+        let now = now.max(self.elapsed);
+        let mut expired = LinkedList::new();
+
+        while let Some((level, slot, deadline)) = self.next_expiration() {
+            if deadline > now {
+                break;
+            }
+
+            self.elapsed = deadline;
+            self.levels[level].occupied &= !(1 << slot);
+            let mut list = mem::take(&mut self.levels[level].slots[slot]);
+
+            if level == 0 {
+                let mut node = list.front_node();
+                while let Some(current) = node {
+                    let id = self.ids.remove(&current).unwrap();
+                    self.timers.remove(&id);
+                    node = unsafe { list.next_node(current) };
+                }
+                expired.append(&mut list);
+            } else {
+                self.cascade(&mut list);
+            }
+        }
+
+        self.elapsed = now;
+        expired
+    }
+
+    pub fn clear(&mut self) {
+        for level in self.levels.iter_mut() {
+            for slot in level.slots.iter_mut() {
+                slot.clear();
+            }
+            level.occupied = 0;
+        }
+        self.timers.clear();
+        self.ids.clear();
+    }
+
+    fn cascade(&mut self, list: &mut LinkedList<T>) {
+        while let Some(node) = list.front_node() {
+            let id = self.ids[&node];
+            let timer = self.timers.get_mut(&id).unwrap();
+            let (level, slot) = slot_for(self.elapsed, timer.deadline);
+
+            unsafe {
+                list.unlink_node(node);
+                self.levels[level].slots[slot].link_back(node);
+            }
+            self.levels[level].occupied |= 1 << slot;
+            timer.level = level;
+            timer.slot = slot;
+        }
+    }
+
+    fn slot_for(&self, deadline: u64) -> (usize, usize) {
+        slot_for(self.elapsed, deadline)
+    }
+
+    // Lowest level first: everything on level `n` is due before the start of
+    // any occupied slot on level `n + 1`.
+    fn next_expiration(&self) -> Option<(usize, usize, u64)> {
+        self.levels
+            .iter()
+            .enumerate()
+            .find(|(_, level)| level.occupied != 0)
+            .map(|(index, level)| {
+                let shift = SLOT_BITS * index as u32;
+                let now_slot = ((self.elapsed >> shift) % SLOTS as u64) as u32;
+                let ahead = level.occupied.rotate_right(now_slot).trailing_zeros();
+                let slot = ((now_slot + ahead) % SLOTS as u32) as usize;
+
+                let level_start = match 1u64.checked_shl(shift + SLOT_BITS) {
+                    Some(range) => self.elapsed & !(range - 1),
+                    None => 0,
+                };
+                (index, slot, level_start + ((slot as u64) << shift))
+            })
+    }
+}
+
+fn slot_for(elapsed: u64, deadline: u64) -> (usize, usize) {
+    let deadline = deadline.max(elapsed);
+    let masked = (elapsed ^ deadline) | (SLOTS as u64 - 1);
+    let level = ((63 - masked.leading_zeros()) / SLOT_BITS) as usize;
+    let slot = (deadline >> (SLOT_BITS * level as u32)) as usize % SLOTS;
+    (level, slot)
+}
+
+impl<T> Default for TimerWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Debug for TimerWheel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TimerWheel")
+            .field("elapsed", &self.elapsed)
+            .field("len", &self.len())
+            .finish()
+    }
+}
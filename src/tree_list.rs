@@ -0,0 +1,765 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{Bound, RangeBounds};
+use core::ptr;
+
+use crate::indexed::random_seed;
+
+// A treap keyed by position: in order, the nodes are the list, and every node
+// knows the size of its subtree so positions can be found on the way down.
+// Random priorities keep it balanced in expectation, which gets split, merge
+// and everything built on them down to O(log n).
+pub struct TreeList<T> {
+    root: Tree<T>,
+    seed: u64,
+}
+
+type Tree<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    priority: u64,
+    size: usize,
+    left: Tree<T>,
+    right: Tree<T>,
+}
+
+pub struct Iter<'a, T> {
+    front: Vec<&'a Node<T>>,
+    back: Vec<&'a Node<T>>,
+    len: usize,
+}
+
+pub struct IterMut<'a, T> {
+    front: Vec<*mut Node<T>>,
+    back: Vec<*mut Node<T>>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T> {
+    list: TreeList<T>,
+}
+
+pub struct CursorMut<'a, T> {
+    list: &'a mut TreeList<T>,
+    index: Option<usize>,
+}
+
+// Follows a link without making a reference to the node on the other end,
+// which `IterMut` may already have handed out an element of.
+unsafe fn child<T>(link: *mut Tree<T>) -> Option<*mut Node<T>> {
+    (*link).as_mut().map(|node| ptr::addr_of_mut!(**node))
+}
+
+// splitmix64's finalizer. A bijection that leaves only 0 at 0, so a non-zero
+// xorshift state stays non-zero.
+fn scramble(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+fn size<T>(tree: &Tree<T>) -> usize {
+    tree.as_ref().map_or(0, |node| node.size)
+}
+
+fn merge<T>(left: Tree<T>, right: Tree<T>) -> Tree<T> {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut left), Some(mut right)) => {
+            if left.priority > right.priority {
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+// The first `at` elements go left, the rest right.
+fn split<T>(tree: Tree<T>, at: usize) -> (Tree<T>, Tree<T>) {
+    let Some(mut node) = tree else {
+        return (None, None);
+    };
+
+    let left_size = size(&node.left);
+    if at <= left_size {
+        let (left, rest) = split(node.left.take(), at);
+        node.left = rest;
+        node.update();
+        (left, Some(node))
+    } else {
+        let (rest, right) = split(node.right.take(), at - left_size - 1);
+        node.right = rest;
+        node.update();
+        (Some(node), right)
+    }
+}
+
+impl<T> Node<T> {
+    fn update(&mut self) {
+        self.size = size(&self.left) + size(&self.right) + 1;
+    }
+}
+
+impl<T> TreeList<T> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            seed: random_seed(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.root = None;
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        let node = self.new_node(elem);
+        self.root = merge(node, self.root.take());
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        let node = self.new_node(elem);
+        self.root = merge(self.root.take(), node);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.remove(self.len().checked_sub(1)?)
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        let mut tree = &self.root;
+        while let Some(node) = tree {
+            let left_size = size(&node.left);
+            if index < left_size {
+                tree = &node.left;
+            } else if index == left_size {
+                return Some(&node.elem);
+            } else {
+                index -= left_size + 1;
+                tree = &node.right;
+            }
+        }
+        None
+    }
+
+    pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        let mut tree = &mut self.root;
+        while let Some(node) = tree {
+            let left_size = size(&node.left);
+            if index < left_size {
+                tree = &mut node.left;
+            } else if index == left_size {
+                return Some(&mut node.elem);
+            } else {
+                index -= left_size + 1;
+                tree = &mut node.right;
+            }
+        }
+        None
+    }
+
+    pub fn insert(&mut self, index: usize, elem: T) {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+
+        let node = self.new_node(elem);
+        let (left, right) = split(self.root.take(), index);
+        self.root = merge(merge(left, node), right);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let (left, rest) = split(self.root.take(), index);
+        let (node, right) = split(rest, 1);
+        self.root = merge(left, right);
+        node.map(|node| node.elem)
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.root = merge(self.root.take(), other.root.take());
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(
+            at <= len,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+
+        let (left, right) = split(self.root.take(), at);
+        self.root = left;
+        // Seeded straight from `next_priority` the new half would hand out
+        // the same priorities as this one, and appending them back together
+        // would pair them up. Scrambling the seed first gives it a sequence
+        // of its own.
+        Self {
+            root: right,
+            seed: scramble(self.next_priority()),
+        }
+    }
+
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    // Stable. Takes the nodes out in order, sorts them and merges them back,
+    // so no element moves and every node keeps its priority. The merging
+    // happens when `Rebuild` drops, which keeps the list whole even if
+    // `compare` panics halfway through.
+    pub fn sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, mut compare: F) {
+        struct Rebuild<'a, T> {
+            list: &'a mut TreeList<T>,
+            nodes: Vec<Box<Node<T>>>,
+        }
+
+        impl<T> Drop for Rebuild<'_, T> {
+            fn drop(&mut self) {
+                for node in self.nodes.drain(..) {
+                    self.list.root = merge(self.list.root.take(), Some(node));
+                }
+            }
+        }
+
+        if self.len() < 2 {
+            return;
+        }
+
+        let mut nodes = Vec::with_capacity(self.len());
+        let mut stack: Vec<Box<Node<T>>> = Vec::new();
+        let mut tree = self.root.take();
+        loop {
+            while let Some(mut node) = tree {
+                tree = node.left.take();
+                stack.push(node);
+            }
+            let Some(mut node) = stack.pop() else {
+                break;
+            };
+            tree = node.right.take();
+            node.size = 1;
+            nodes.push(node);
+        }
+
+        let mut rebuild = Rebuild { list: self, nodes };
+        rebuild.nodes.sort_by(|a, b| compare(&a.elem, &b.elem));
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.range(..)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let len = self.len();
+        let root = unsafe { child(ptr::addr_of_mut!(self.root)) };
+
+        let mut iter = IterMut {
+            front: Vec::new(),
+            back: Vec::new(),
+            len,
+            _boo: PhantomData,
+        };
+        if let Some(root) = root {
+            unsafe {
+                iter.push_left(root);
+                iter.push_right(root);
+            }
+        }
+        iter
+    }
+
+    // Iterates over positions in `range` without walking to it first.
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> Iter<'_, T> {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end && end <= len,
+            "range {}..{} out of bounds for len {}",
+            start,
+            end,
+            len
+        );
+
+        let mut iter = Iter {
+            front: Vec::new(),
+            back: Vec::new(),
+            len: end - start,
+        };
+        if start < end {
+            iter.seek_front(&self.root, start);
+            iter.seek_back(&self.root, end - 1);
+        }
+        iter
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            list: self,
+            index: None,
+        }
+    }
+
+    fn new_node(&mut self, elem: T) -> Tree<T> {
+        Some(Box::new(Node {
+            elem,
+            priority: self.next_priority(),
+            size: 1,
+            left: None,
+            right: None,
+        }))
+    }
+
+    fn next_priority(&mut self) -> u64 {
+        // xorshift64
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl<T> Drop for TreeList<T> {
+    // Dropping the boxes recursively would only go O(log n) deep in
+    // expectation, but doing it by hand doesn't rely on the priorities.
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T> Default for TreeList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for TreeList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for TreeList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for TreeList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for TreeList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for TreeList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for TreeList<T> {}
+
+impl<T: PartialOrd> PartialOrd for TreeList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+impl<T: Ord> Ord for TreeList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+impl<T: Hash> Hash for TreeList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TreeList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut TreeList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for TreeList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+// Both stacks hold the nodes whose element hasn't been visited yet on the way
+// to the next element from that end, and `len` stops the two from crossing.
+impl<'a, T> Iter<'a, T> {
+    fn seek_front(&mut self, mut tree: &'a Tree<T>, mut index: usize) {
+        while let Some(node) = tree {
+            let left_size = size(&node.left);
+            if index <= left_size {
+                self.front.push(node);
+                if index == left_size {
+                    return;
+                }
+                tree = &node.left;
+            } else {
+                index -= left_size + 1;
+                tree = &node.right;
+            }
+        }
+    }
+
+    fn seek_back(&mut self, mut tree: &'a Tree<T>, mut index: usize) {
+        while let Some(node) = tree {
+            let left_size = size(&node.left);
+            if index >= left_size {
+                self.back.push(node);
+                if index == left_size {
+                    return;
+                }
+                index -= left_size + 1;
+                tree = &node.right;
+            } else {
+                tree = &node.left;
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.front.pop()?;
+        self.len -= 1;
+        self.seek_front(&node.right, 0);
+        Some(&node.elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.back.pop()?;
+        self.len -= 1;
+        self.seek_back(&node.left, size(&node.left).wrapping_sub(1));
+        Some(&node.elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> IterMut<'a, T> {
+    unsafe fn push_left(&mut self, mut node: *mut Node<T>) {
+        loop {
+            self.front.push(node);
+            match child(ptr::addr_of_mut!((*node).left)) {
+                Some(left) => node = left,
+                None => break,
+            }
+        }
+    }
+
+    unsafe fn push_right(&mut self, mut node: *mut Node<T>) {
+        loop {
+            self.back.push(node);
+            match child(ptr::addr_of_mut!((*node).right)) {
+                Some(right) => node = right,
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.front.pop()?;
+        self.len -= 1;
+        unsafe {
+            if let Some(right) = child(ptr::addr_of_mut!((*node).right)) {
+                self.push_left(right);
+            }
+            Some(&mut *ptr::addr_of_mut!((*node).elem))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.back.pop()?;
+        self.len -= 1;
+        unsafe {
+            if let Some(left) = child(ptr::addr_of_mut!((*node).left)) {
+                self.push_right(left);
+            }
+            Some(&mut *ptr::addr_of_mut!((*node).elem))
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len(), Some(self.list.len()))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len()
+    }
+}
+
+// Same shape as `LinkedList`'s cursor, but it only remembers an index, so
+// every access is a walk down the tree.
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn as_list(&self) -> &TreeList<T> {
+        self.list
+    }
+
+    pub fn move_next(&mut self) {
+        let len = self.list.len();
+        self.index = match self.index {
+            Some(index) if index + 1 < len => Some(index + 1),
+            Some(_) => None,
+            None if len > 0 => Some(0),
+            None => None,
+        };
+    }
+
+    pub fn move_prev(&mut self) {
+        let len = self.list.len();
+        self.index = match self.index {
+            Some(0) => None,
+            Some(index) => Some(index - 1),
+            None => len.checked_sub(1),
+        };
+    }
+
+    // Jumps straight to `index`, or the ghost if it's out of bounds.
+    pub fn seek(&mut self, index: usize) {
+        self.index = Some(index).filter(|&index| index < self.list.len());
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.list.get_mut(self.index?)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = self.index.map_or(0, |index| index + 1);
+        self.list.get_mut(next)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.index {
+            Some(index) => index.checked_sub(1)?,
+            None => self.list.len().checked_sub(1)?,
+        };
+        self.list.get_mut(prev)
+    }
+
+    pub fn split_before(&mut self) -> TreeList<T> {
+        match self.index {
+            Some(index) => {
+                let rest = self.list.split_off(index);
+                self.index = Some(0);
+                mem::replace(self.list, rest)
+            }
+            None => mem::take(self.list),
+        }
+    }
+
+    pub fn split_after(&mut self) -> TreeList<T> {
+        match self.index {
+            Some(index) => self.list.split_off(index + 1),
+            None => mem::take(self.list),
+        }
+    }
+
+    pub fn splice_before(&mut self, mut input: TreeList<T>) {
+        match self.index {
+            Some(index) => {
+                let len = input.len();
+                let rest = split(self.list.root.take(), index);
+                self.list.root = merge(merge(rest.0, input.root.take()), rest.1);
+                self.index = Some(index + len);
+            }
+            None => self.list.append(&mut input),
+        }
+    }
+
+    pub fn splice_after(&mut self, mut input: TreeList<T>) {
+        let at = self.index.map_or(0, |index| index + 1);
+        let rest = split(self.list.root.take(), at);
+        self.list.root = merge(merge(rest.0, input.root.take()), rest.1);
+    }
+
+    pub fn insert_before(&mut self, elem: T) {
+        match self.index {
+            Some(index) => {
+                self.list.insert(index, elem);
+                self.index = Some(index + 1);
+            }
+            None => self.list.push_back(elem),
+        }
+    }
+
+    pub fn insert_after(&mut self, elem: T) {
+        let at = self.index.map_or(0, |index| index + 1);
+        self.list.insert(at, elem);
+    }
+
+    // Moves on to the next element, or the ghost if there isn't one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let index = self.index?;
+        let elem = self.list.remove(index);
+        if index >= self.list.len() {
+            self.index = None;
+        }
+        elem
+    }
+}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
@@ -0,0 +1,326 @@
+use core::error::Error;
+use core::fmt::{self, Debug};
+use core::mem;
+
+use crate::linked_list::{self, LinkedList};
+
+pub trait Weigher<T> {
+    fn weigh(&self, elem: &T) -> usize;
+}
+
+impl<T, F: Fn(&T) -> usize> Weigher<T> for F {
+    fn weigh(&self, elem: &T) -> usize {
+        self(elem)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ByteLen;
+
+impl<T: AsRef<[u8]>> Weigher<T> for ByteLen {
+    fn weigh(&self, elem: &T) -> usize {
+        elem.as_ref().len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightPolicy {
+    Reject,
+    EvictFront,
+    EvictBack,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverBudget;
+
+// Each element is weighed once on the way in and its weight kept next to it,
+// so the total never depends on the weigher answering the same way twice.
+// There's still no `front_mut`/`back_mut`: a change through one would leave
+// the stored weight stale.
+pub struct WeightedDeque<T, W> {
+    list: LinkedList<(usize, T)>,
+    weigher: W,
+    weight: usize,
+    budget: usize,
+    policy: WeightPolicy,
+}
+
+pub struct Iter<'a, T> {
+    iter: linked_list::Iter<'a, (usize, T)>,
+}
+
+impl<T, W: Weigher<T>> WeightedDeque<T, W> {
+    pub fn new(budget: usize, policy: WeightPolicy, weigher: W) -> Self {
+        Self {
+            list: LinkedList::new(),
+            weigher,
+            weight: 0,
+            budget,
+            policy,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.budget - self.weight
+    }
+
+    pub fn policy(&self) -> WeightPolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: WeightPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn weigher(&self) -> &W {
+        &self.weigher
+    }
+
+    // Changes the budget, returning whatever had to go in order. `Reject`
+    // has nothing to refuse here, so it gives up the front like `EvictFront`.
+    pub fn set_budget(&mut self, budget: usize) -> LinkedList<T> {
+        self.budget = budget;
+        let mut evicted = LinkedList::new();
+        while self.weight > budget {
+            if self.policy == WeightPolicy::EvictBack {
+                evicted.push_front(self.pop_back().unwrap());
+            } else {
+                evicted.push_back(self.pop_front().unwrap());
+            }
+        }
+        evicted
+    }
+
+    pub fn push_front(&mut self, elem: T) -> Result<LinkedList<T>, T> {
+        let weight = self.weigher.weigh(&elem);
+        match self.make_room(weight) {
+            Some(evicted) => {
+                self.list.push_front((weight, elem));
+                self.weight += weight;
+                Ok(evicted)
+            }
+            None => Err(elem),
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) -> Result<LinkedList<T>, T> {
+        let weight = self.weigher.weigh(&elem);
+        match self.make_room(weight) {
+            Some(evicted) => {
+                self.list.push_back((weight, elem));
+                self.weight += weight;
+                Ok(evicted)
+            }
+            None => Err(elem),
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (weight, elem) = self.list.pop_front()?;
+        self.weight -= weight;
+        Some(elem)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let (weight, elem) = self.list.pop_back()?;
+        self.weight -= weight;
+        Some(elem)
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.list.front().map(|(_, elem)| elem)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.list.back().map(|(_, elem)| elem)
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.weight = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.list.iter(),
+        }
+    }
+
+    // Every node also holds a weight, so this moves each element into a new
+    // node: O(n), with an allocation per element.
+    pub fn into_list(self) -> LinkedList<T> {
+        self.list.into_iter().map(|(_, elem)| elem).collect()
+    }
+
+    // Moves all of `other` onto the back, first evicting from `self` by the
+    // same rules as a push. On `Err` neither deque is touched.
+    pub fn append(&mut self, other: &mut Self) -> Result<LinkedList<T>, OverBudget> {
+        let evicted = self.make_room(other.weight).ok_or(OverBudget)?;
+        self.list.append(&mut other.list);
+        self.weight += other.weight;
+        other.weight = 0;
+        Ok(evicted)
+    }
+
+    // Moves all of `other` in before the element at `at`, first evicting
+    // from `self` by the same rules as a push. `at` is taken after eviction:
+    // it's clamped to the new length, and shifted down by however many
+    // elements were evicted from the front. On `Err` neither deque is touched.
+    pub fn splice(&mut self, at: usize, other: &mut Self) -> Result<LinkedList<T>, OverBudget> {
+        assert!(
+            at <= self.len(),
+            "splice index (is {}) should be <= len (is {})",
+            at,
+            self.len()
+        );
+
+        let evicted = self.make_room(other.weight).ok_or(OverBudget)?;
+        let at = match self.policy {
+            WeightPolicy::EvictFront => at.saturating_sub(evicted.len()),
+            _ => at.min(self.len()),
+        };
+
+        let mut tail = self.list.split_off(at);
+        self.list.append(&mut other.list);
+        self.list.append(&mut tail);
+        self.weight += other.weight;
+        other.weight = 0;
+        Ok(evicted)
+    }
+
+    // Evicts until `weight` more fits, or returns `None` without touching
+    // anything if it can't.
+    fn make_room(&mut self, weight: usize) -> Option<LinkedList<T>> {
+        if weight > self.budget {
+            return None;
+        }
+
+        let mut evicted = LinkedList::new();
+        if weight <= self.remaining() {
+            return Some(evicted);
+        }
+
+        match self.policy {
+            WeightPolicy::Reject => return None,
+            WeightPolicy::EvictFront => {
+                while weight > self.remaining() {
+                    evicted.push_back(self.pop_front().unwrap());
+                }
+            }
+            WeightPolicy::EvictBack => {
+                while weight > self.remaining() {
+                    evicted.push_front(self.pop_back().unwrap());
+                }
+            }
+        }
+        Some(evicted)
+    }
+}
+
+impl<T, W: Weigher<T> + Clone> WeightedDeque<T, W> {
+    // Splits off everything from `at` on into a deque with the same budget
+    // and policy. One walk from the nearer end, adding up stored weights on
+    // the way.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.list.len();
+        assert!(
+            at <= len,
+            "split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+
+        let mut cursor = self.list.cursor_mut();
+        let (list, tail_weight) = if at > len / 2 {
+            let mut tail_weight = 0;
+            for _ in at..len {
+                cursor.move_prev();
+                tail_weight += cursor.current().unwrap().0;
+            }
+            // That leaves the cursor on `at`, so what comes off is the head.
+            let head = cursor.split_before();
+            (mem::replace(&mut self.list, head), tail_weight)
+        } else {
+            let mut head_weight = 0;
+            for _ in 0..at {
+                cursor.move_next();
+                head_weight += cursor.current().unwrap().0;
+            }
+            (cursor.split_after(), self.weight - head_weight)
+        };
+
+        self.weight -= tail_weight;
+        Self {
+            list,
+            weigher: self.weigher.clone(),
+            weight: tail_weight,
+            budget: self.budget,
+            policy: self.policy,
+        }
+    }
+}
+
+impl<T: Debug, W> Debug for WeightedDeque<T, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.list.iter().map(|(_, elem)| elem))
+            .finish()
+    }
+}
+
+impl<'a, T, W: Weigher<T>> IntoIterator for &'a WeightedDeque<T, W> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(_, elem)| elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(_, elem)| elem)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl fmt::Display for OverBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("weight exceeds the deque's budget")
+    }
+}
+
+impl Error for OverBudget {}
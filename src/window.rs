@@ -0,0 +1,305 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+use crate::deque::Deque;
+use crate::linked_list::LinkedList;
+
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Compare<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Natural;
+
+impl<T: Ord> Compare<T> for Natural {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reversed<C>(pub C);
+
+impl<T, C: Compare<T>> Compare<T> for Reversed<C> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.0.compare(b, a)
+    }
+}
+
+// Keeps only the values that can still become the least (by `cmp`) once
+// everything pushed before them has expired. That makes the list increasing
+// from front to back, so the least live value is always at the front.
+//
+// All three windows sit on any `Deque`, `LinkedList` unless told otherwise.
+pub struct MonotonicDeque<T, C = Natural, D = LinkedList<(usize, T)>> {
+    list: D,
+    cmp: C,
+    next: usize,
+    _elem: PhantomData<T>,
+}
+
+pub struct SlidingWindow<T, C = Natural, D = LinkedList<(usize, T)>> {
+    min: MonotonicDeque<T, C, D>,
+    max: MonotonicDeque<T, Reversed<C>, D>,
+    start: usize,
+}
+
+// Two-stack queue. Values pushed go on `back` with a running aggregate, and
+// once `front` runs dry everything is moved over, each value storing the
+// aggregate of itself and everything behind it in `front`. Only needs `op`
+// to be associative.
+pub struct AggregateWindow<T, F, Front = LinkedList<(T, T)>, Back = LinkedList<T>> {
+    front: Front,
+    back: Back,
+    back_agg: Option<T>,
+    op: F,
+    start: usize,
+    next: usize,
+}
+
+impl<T: Ord> MonotonicDeque<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T: Ord> Default for MonotonicDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> MonotonicDeque<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self::with_deque(cmp, LinkedList::new())
+    }
+}
+
+impl<T, C: Compare<T>, D: Deque<(usize, T)>> MonotonicDeque<T, C, D> {
+    // Whatever is in `deque` already is thrown away.
+    pub fn with_deque(cmp: C, mut deque: D) -> Self {
+        deque.clear();
+        Self {
+            list: deque,
+            cmp,
+            next: 0,
+            _elem: PhantomData,
+        }
+    }
+
+    pub fn push(&mut self, value: T) -> usize {
+        while let Some((_, back)) = self.list.back() {
+            if self.cmp.compare(back, &value) == Ordering::Less {
+                break;
+            }
+            self.list.pop_back();
+        }
+
+        let index = self.next;
+        self.next += 1;
+        self.list.push_back((index, value));
+        index
+    }
+
+    // Drops everything pushed before `up_to`.
+    pub fn expire(&mut self, up_to: usize) {
+        while let Some(&(index, _)) = self.list.front() {
+            if index >= up_to {
+                break;
+            }
+            self.list.pop_front();
+        }
+    }
+
+    pub fn best(&self) -> Option<&T> {
+        self.list.front().map(|(_, value)| value)
+    }
+
+    pub fn best_index(&self) -> Option<usize> {
+        self.list.front().map(|&(index, _)| index)
+    }
+
+    pub fn next_index(&self) -> usize {
+        self.next
+    }
+
+    // Candidates only, not everything that was pushed.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+}
+
+impl<T: Debug, C, D: Deque<(usize, T)>> Debug for MonotonicDeque<T, C, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.list.iter()).finish()
+    }
+}
+
+impl<T: Ord + Clone> SlidingWindow<T> {
+    pub fn new() -> Self {
+        Self::with_comparator(Natural)
+    }
+}
+
+impl<T: Ord + Clone> Default for SlidingWindow<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone> SlidingWindow<T, C> {
+    pub fn with_comparator(cmp: C) -> Self {
+        Self::with_deques(cmp, LinkedList::new(), LinkedList::new())
+    }
+}
+
+impl<T: Clone, C: Compare<T> + Clone, D: Deque<(usize, T)>> SlidingWindow<T, C, D> {
+    pub fn with_deques(cmp: C, min: D, max: D) -> Self {
+        Self {
+            min: MonotonicDeque::with_deque(cmp.clone(), min),
+            max: MonotonicDeque::with_deque(Reversed(cmp), max),
+            start: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: T) -> usize {
+        self.max.push(value.clone());
+        self.min.push(value)
+    }
+
+    pub fn expire(&mut self, up_to: usize) {
+        let up_to = up_to.min(self.min.next_index());
+        if up_to > self.start {
+            self.start = up_to;
+            self.min.expire(up_to);
+            self.max.expire(up_to);
+        }
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.min.best()
+    }
+
+    pub fn max(&self) -> Option<&T> {
+        self.max.best()
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn len(&self) -> usize {
+        self.min.next_index() - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> AggregateWindow<T, F> {
+    pub fn new(op: F) -> Self {
+        Self::with_deques(op, LinkedList::new(), LinkedList::new())
+    }
+}
+
+impl<T, F, Front, Back> AggregateWindow<T, F, Front, Back>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+    Front: Deque<(T, T)>,
+    Back: Deque<T>,
+{
+    // Whatever is in `front` and `back` already is thrown away.
+    pub fn with_deques(op: F, mut front: Front, mut back: Back) -> Self {
+        front.clear();
+        back.clear();
+        Self {
+            front,
+            back,
+            back_agg: None,
+            op,
+            start: 0,
+            next: 0,
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn len(&self) -> usize {
+        self.next - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn push(&mut self, value: T) -> usize {
+        self.back_agg = Some(match self.back_agg.take() {
+            Some(agg) => (self.op)(&agg, &value),
+            None => value.clone(),
+        });
+        self.back.push_back(value);
+
+        let index = self.next;
+        self.next += 1;
+        index
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.front.is_empty() {
+            self.flip();
+        }
+
+        let (value, _) = self.front.pop_front()?;
+        self.start += 1;
+        Some(value)
+    }
+
+    pub fn expire(&mut self, up_to: usize) {
+        while self.start < up_to && self.pop_front().is_some() {}
+    }
+
+    pub fn aggregate(&self) -> Option<T> {
+        match (self.front.front(), &self.back_agg) {
+            (Some((_, front)), Some(back)) => Some((self.op)(front, back)),
+            (Some((_, front)), None) => Some(front.clone()),
+            (None, back) => back.clone(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.front.clear();
+        self.back.clear();
+        self.back_agg = None;
+        self.start = self.next;
+    }
+
+    fn flip(&mut self) {
+        self.back_agg = None;
+        while let Some(value) = self.back.pop_back() {
+            let agg = match self.front.front() {
+                Some((_, behind)) => (self.op)(&value, behind),
+                None => value.clone(),
+            };
+            self.front.push_front((value, agg));
+        }
+    }
+}
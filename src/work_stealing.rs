@@ -0,0 +1,108 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::linked_list::LinkedList;
+
+pub struct Worker<T> {
+    shared: Arc<Mutex<LinkedList<T>>>,
+}
+
+pub struct Stealer<T> {
+    shared: Arc<Mutex<LinkedList<T>>>,
+}
+
+fn lock<T>(shared: &Mutex<LinkedList<T>>) -> MutexGuard<'_, LinkedList<T>> {
+    // A panic while holding the lock can't leave the list half-linked, every
+    // operation below finishes its pointer surgery before returning.
+    shared
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl<T> Worker<T> {
+    pub fn new() -> Self {
+        Self {
+            shared: Arc::new(Mutex::new(LinkedList::new())),
+        }
+    }
+
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    pub fn push(&self, elem: T) {
+        lock(&self.shared).push_back(elem);
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        lock(&self.shared).pop_back()
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.shared).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        lock(&self.shared).is_empty()
+    }
+}
+
+impl<T> Default for Worker<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stealer<T> {
+    pub fn steal(&self) -> Option<T> {
+        lock(&self.shared).pop_front()
+    }
+
+    pub fn steal_half(&self) -> LinkedList<T> {
+        let mut list = lock(&self.shared);
+        let count = list.len().div_ceil(2);
+
+        if count == list.len() {
+            return std::mem::take(&mut *list);
+        }
+
+        // The kept half is never longer than the stolen one, so walking in
+        // from the back is the shorter trip.
+        let keep = list.len() - count;
+        let mut cursor = list.cursor_mut();
+        for _ in 0..keep {
+            cursor.move_prev();
+        }
+        cursor.split_before()
+    }
+
+    pub fn steal_half_into(&self, dest: &Worker<T>) -> Option<T> {
+        let mut batch = self.steal_half();
+        let first = batch.pop_front();
+
+        if !batch.is_empty() {
+            let mut list = lock(&dest.shared);
+            let mut cursor = list.cursor_mut();
+            cursor.splice_before(batch);
+        }
+
+        first
+    }
+
+    pub fn len(&self) -> usize {
+        lock(&self.shared).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        lock(&self.shared).is_empty()
+    }
+}
+
+impl<T> Clone for Stealer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
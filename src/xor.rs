@@ -0,0 +1,539 @@
+use alloc::boxed::Box;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+
+// Each node stores the addresses of both neighbours XORed together, so it
+// costs one word of links instead of two. Walking needs the node you came
+// from to recover the one you're going to, and since the links are the same
+// read either way round, reversing is just swapping the ends.
+//
+// Addresses go through exposed provenance, so a pointer rebuilt from an XOR
+// is allowed to access the node it came from. A missing neighbour is 0.
+pub struct XorLinkedList<T> {
+    front: Link<T>,
+    back: Link<T>,
+    len: usize,
+    _boo: PhantomData<T>,
+}
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
+    link: usize,
+    elem: T,
+}
+
+// `prev` is the node behind `front` as seen from the front, and `next` the
+// node behind `back` as seen from the back.
+pub struct Iter<'a, T> {
+    front: Link<T>,
+    prev: Link<T>,
+    back: Link<T>,
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a T>,
+}
+
+pub struct IterMut<'a, T> {
+    front: Link<T>,
+    prev: Link<T>,
+    back: Link<T>,
+    next: Link<T>,
+    len: usize,
+    _boo: PhantomData<&'a mut T>,
+}
+
+pub struct IntoIter<T> {
+    list: XorLinkedList<T>,
+}
+
+// Holds on to the node before the current one as well, since that's what it
+// takes to step in either direction.
+pub struct CursorMut<'a, T> {
+    list: &'a mut XorLinkedList<T>,
+    prev: Link<T>,
+    current: Link<T>,
+    index: Option<usize>,
+}
+
+fn addr<T>(link: Link<T>) -> usize {
+    link.map_or(0, |node| node.as_ptr().expose_provenance())
+}
+
+fn from_addr<T>(addr: usize) -> Link<T> {
+    NonNull::new(ptr::with_exposed_provenance_mut(addr))
+}
+
+fn new_node<T>(elem: T, link: usize) -> NonNull<Node<T>> {
+    unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Node { link, elem }))) }
+}
+
+// Safety: `node` must be live, and `from` one of its neighbours (or None if
+// it's at an end). Returns the neighbour on the other side.
+unsafe fn step<T>(node: NonNull<Node<T>>, from: Link<T>) -> Link<T> {
+    from_addr((*node.as_ptr()).link ^ addr(from))
+}
+
+// Swaps `old` for `new` among `node`'s neighbours, if there is a node.
+unsafe fn relink<T>(node: Link<T>, old: Link<T>, new: Link<T>) {
+    if let Some(node) = node {
+        (*node.as_ptr()).link ^= addr(old) ^ addr(new);
+    }
+}
+
+impl<T> XorLinkedList<T> {
+    pub fn new() -> Self {
+        Self {
+            front: None,
+            back: None,
+            len: 0,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, elem: T) {
+        unsafe {
+            let new = Some(new_node(elem, addr(self.front)));
+            relink(self.front, None, new);
+            if self.back.is_none() {
+                self.back = new;
+            }
+            self.front = new;
+            self.len += 1;
+        }
+    }
+
+    pub fn push_back(&mut self, elem: T) {
+        unsafe {
+            let new = Some(new_node(elem, addr(self.back)));
+            relink(self.back, None, new);
+            if self.front.is_none() {
+                self.front = new;
+            }
+            self.back = new;
+            self.len += 1;
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.front.map(|node| unsafe {
+            let next = step(node, None);
+            relink(next, Some(node), None);
+            self.front = next;
+            if next.is_none() {
+                self.back = None;
+            }
+            self.len -= 1;
+            Box::from_raw(node.as_ptr()).elem
+        })
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.back.map(|node| unsafe {
+            let prev = step(node, None);
+            relink(prev, Some(node), None);
+            self.back = prev;
+            if prev.is_none() {
+                self.front = None;
+            }
+            self.len -= 1;
+            Box::from_raw(node.as_ptr()).elem
+        })
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        unsafe { self.front.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.front.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        unsafe { self.back.map(|node| &(*node.as_ptr()).elem) }
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        unsafe { self.back.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn reverse(&mut self) {
+        core::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    pub fn clear(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            front: self.front,
+            prev: None,
+            back: self.back,
+            next: None,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            front: self.front,
+            prev: None,
+            back: self.back,
+            next: None,
+            len: self.len,
+            _boo: PhantomData,
+        }
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            list: self,
+            prev: None,
+            current: None,
+            index: None,
+        }
+    }
+}
+
+impl<T> Drop for XorLinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T> Default for XorLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for XorLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Extend<T> for XorLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push_back(elem);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for XorLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Self::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T: Debug> Debug for XorLinkedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for XorLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other)
+    }
+}
+
+impl<T: Eq> Eq for XorLinkedList<T> {}
+
+impl<T: Hash> Hash for XorLinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for item in self {
+            item.hash(state)
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a XorLinkedList<T> {
+    type IntoIter = Iter<'a, T>;
+    type Item = &'a T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut XorLinkedList<T> {
+    type IntoIter = IterMut<'a, T>;
+    type Item = &'a mut T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T> IntoIterator for XorLinkedList<T> {
+    type IntoIter = IntoIter<T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|node| unsafe {
+            self.len -= 1;
+            self.front = step(node, self.prev);
+            self.prev = Some(node);
+            &(*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| unsafe {
+            self.len -= 1;
+            self.back = step(node, self.next);
+            self.next = Some(node);
+            &(*node.as_ptr()).elem
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.front.map(|node| unsafe {
+            self.len -= 1;
+            self.front = step(node, self.prev);
+            self.prev = Some(node);
+            &mut (*node.as_ptr()).elem
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.back.map(|node| unsafe {
+            self.len -= 1;
+            self.back = step(node, self.next);
+            self.next = Some(node);
+            &mut (*node.as_ptr()).elem
+        })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {
+    fn len(&self) -> usize {
+        self.list.len
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    pub fn as_list(&self) -> &XorLinkedList<T> {
+        self.list
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            Some(node) => unsafe {
+                let next = step(node, self.prev);
+                if next.is_some() {
+                    self.prev = self.current;
+                    self.current = next;
+                    self.index = self.index.map(|index| index + 1);
+                } else {
+                    self.move_to_ghost();
+                }
+            },
+            None => {
+                self.current = self.list.front;
+                self.index = self.current.map(|_| 0);
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            Some(_) if self.prev.is_none() => self.move_to_ghost(),
+            Some(node) => unsafe {
+                let prev = self.prev.unwrap();
+                self.prev = step(prev, Some(node));
+                self.current = Some(prev);
+                self.index = self.index.map(|index| index - 1);
+            },
+            None => unsafe {
+                self.current = self.list.back;
+                self.prev = self.current.and_then(|back| step(back, None));
+                self.index = self.list.len.checked_sub(1);
+            },
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        unsafe {
+            let next = match self.current {
+                Some(node) => step(node, self.prev),
+                None => self.list.front,
+            };
+            next.map(|node| &mut (*node.as_ptr()).elem)
+        }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            Some(_) => self.prev,
+            None => self.list.back,
+        };
+        unsafe { prev.map(|node| &mut (*node.as_ptr()).elem) }
+    }
+
+    // On the ghost this pushes onto the back, like `LinkedList`'s cursor.
+    pub fn insert_before(&mut self, elem: T) {
+        let Some(node) = self.current else {
+            return self.list.push_back(elem);
+        };
+
+        unsafe {
+            let new = Some(new_node(elem, addr(self.prev) ^ addr(self.current)));
+            relink(self.prev, self.current, new);
+            relink(Some(node), self.prev, new);
+            if self.prev.is_none() {
+                self.list.front = new;
+            }
+            self.prev = new;
+            self.index = self.index.map(|index| index + 1);
+            self.list.len += 1;
+        }
+    }
+
+    // On the ghost this pushes onto the front.
+    pub fn insert_after(&mut self, elem: T) {
+        let Some(node) = self.current else {
+            return self.list.push_front(elem);
+        };
+
+        unsafe {
+            let next = step(node, self.prev);
+            let new = Some(new_node(elem, addr(self.current) ^ addr(next)));
+            relink(Some(node), next, new);
+            relink(next, self.current, new);
+            if next.is_none() {
+                self.list.back = new;
+            }
+            self.list.len += 1;
+        }
+    }
+
+    // Moves on to the next element, or the ghost if there isn't one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let node = self.current?;
+        unsafe {
+            let next = step(node, self.prev);
+            relink(self.prev, self.current, next);
+            relink(next, self.current, self.prev);
+            if self.prev.is_none() {
+                self.list.front = next;
+            }
+            if next.is_none() {
+                self.list.back = self.prev;
+            }
+            self.list.len -= 1;
+
+            self.current = next;
+            if next.is_none() {
+                self.move_to_ghost();
+            }
+            Some(Box::from_raw(node.as_ptr()).elem)
+        }
+    }
+
+    fn move_to_ghost(&mut self) {
+        self.prev = None;
+        self.current = None;
+        self.index = None;
+    }
+}
+
+unsafe impl<T: Send> Send for XorLinkedList<T> {}
+unsafe impl<T: Sync> Sync for XorLinkedList<T> {}
+
+unsafe impl<'a, T: Sync> Send for Iter<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for Iter<'a, T> {}
+
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}